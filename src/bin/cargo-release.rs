@@ -26,6 +26,8 @@ fn run() -> Result<(), error::CliError> {
         Some(Step::Tag(config)) => config.run(),
         Some(Step::Push(config)) => config.run(),
         Some(Step::Config(config)) => config.run(),
+        Some(Step::Status(config)) => config.run(),
+        Some(Step::Undo(config)) => config.run(),
         None => release_matches.release.run(),
     }
 }
@@ -84,6 +86,8 @@ pub enum Step {
     Tag(steps::tag::TagStep),
     Push(steps::push::PushStep),
     Config(steps::config::ConfigStep),
+    Status(steps::status::StatusStep),
+    Undo(steps::undo::UndoStep),
 }
 
 #[derive(clap::Args, Debug, Clone)]
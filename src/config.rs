@@ -17,10 +17,13 @@ pub struct Config {
     pub sign_commit: Option<bool>,
     pub sign_tag: Option<bool>,
     pub push_remote: Option<String>,
-    pub registry: Option<String>,
+    pub registry: Option<Registries>,
     pub release: Option<bool>,
     pub publish: Option<bool>,
     pub verify: Option<bool>,
+    pub publish_wait: Option<PublishWait>,
+    pub publish_timeout: Option<u64>,
+    pub publish_retries: Option<usize>,
     pub owners: Option<Vec<String>>,
     pub push: Option<bool>,
     pub push_options: Option<Vec<String>>,
@@ -29,6 +32,15 @@ pub struct Config {
     pub pre_release_commit_message: Option<String>,
     pub pre_release_replacements: Option<Vec<Replace>>,
     pub pre_release_hook: Option<Command>,
+    pub post_commit_hook: Option<Command>,
+    pub pre_publish_hook: Option<Command>,
+    pub post_publish_hook: Option<Command>,
+    pub post_tag_hook: Option<Command>,
+    pub post_push_hook: Option<Command>,
+    pub workspace_pre_release_hook: Option<Command>,
+    pub changelog: Option<PathBuf>,
+    pub post_release_version: Option<String>,
+    pub post_release_commit_message: Option<String>,
     pub tag_message: Option<String>,
     pub tag_prefix: Option<String>,
     pub tag_name: Option<String>,
@@ -39,7 +51,17 @@ pub struct Config {
     pub metadata: Option<MetadataPolicy>,
     pub target: Option<String>,
     pub rate_limit: RateLimit,
+    pub publish_jobs: Option<usize>,
     pub certs_source: Option<CertsSource>,
+    /// Config files to inherit settings from
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extends: Option<Vec<String>>,
+    /// Named sets of settings, selected with `--profile`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub profile: Option<std::collections::BTreeMap<String, Self>>,
+    /// Settings for packages whose name matches the key, which may be a glob
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub package: Option<std::collections::BTreeMap<String, Self>>,
 }
 
 impl Config {
@@ -61,10 +83,13 @@ impl Config {
             sign_commit: Some(empty.sign_commit()),
             sign_tag: Some(empty.sign_tag()),
             push_remote: Some(empty.push_remote().to_owned()),
-            registry: empty.registry().map(|s| s.to_owned()),
+            registry: empty.registry.clone(),
             release: Some(empty.release()),
             publish: Some(empty.publish()),
             verify: Some(empty.verify()),
+            publish_wait: Some(empty.publish_wait()),
+            publish_timeout: Some(empty.publish_timeout()),
+            publish_retries: Some(empty.publish_retries()),
             owners: Some(empty.owners().to_vec()),
             push: Some(empty.push()),
             push_options: Some(
@@ -80,6 +105,15 @@ impl Config {
             pre_release_commit_message: Some(empty.pre_release_commit_message().to_owned()),
             pre_release_replacements: Some(empty.pre_release_replacements().to_vec()),
             pre_release_hook: empty.pre_release_hook().cloned(),
+            post_commit_hook: empty.post_commit_hook().cloned(),
+            pre_publish_hook: empty.pre_publish_hook().cloned(),
+            post_publish_hook: empty.post_publish_hook().cloned(),
+            post_tag_hook: empty.post_tag_hook().cloned(),
+            post_push_hook: empty.post_push_hook().cloned(),
+            workspace_pre_release_hook: empty.workspace_pre_release_hook().cloned(),
+            changelog: empty.changelog().map(|p| p.to_owned()),
+            post_release_version: empty.post_release_version().map(|s| s.to_owned()),
+            post_release_commit_message: Some(empty.post_release_commit_message().to_owned()),
             tag_message: Some(empty.tag_message().to_owned()),
             tag_prefix: None, // Skipping, its location dependent
             tag_name: Some(empty.tag_name().to_owned()),
@@ -90,7 +124,11 @@ impl Config {
             metadata: Some(empty.metadata()),
             target: None,
             rate_limit: RateLimit::from_defaults(),
+            publish_jobs: Some(empty.publish_jobs()),
             certs_source: Some(empty.certs_source()),
+            extends: None, // Skipping, resolved while loading
+            profile: None, // Skipping, selected while loading
+            package: None, // Skipping, selected while loading
         }
     }
 
@@ -108,8 +146,8 @@ impl Config {
         if let Some(push_remote) = source.push_remote.as_deref() {
             self.push_remote = Some(push_remote.to_owned());
         }
-        if let Some(registry) = source.registry.as_deref() {
-            self.registry = Some(registry.to_owned());
+        if let Some(registry) = source.registry.as_ref() {
+            self.registry = Some(registry.clone());
         }
         if let Some(release) = source.release {
             self.release = Some(release);
@@ -120,6 +158,15 @@ impl Config {
         if let Some(verify) = source.verify {
             self.verify = Some(verify);
         }
+        if let Some(publish_wait) = source.publish_wait {
+            self.publish_wait = Some(publish_wait);
+        }
+        if let Some(publish_timeout) = source.publish_timeout {
+            self.publish_timeout = Some(publish_timeout);
+        }
+        if let Some(publish_retries) = source.publish_retries {
+            self.publish_retries = Some(publish_retries);
+        }
         if let Some(owners) = source.owners.as_deref() {
             self.owners = Some(owners.to_owned());
         }
@@ -144,6 +191,33 @@ impl Config {
         if let Some(pre_release_hook) = source.pre_release_hook.as_ref() {
             self.pre_release_hook = Some(pre_release_hook.to_owned());
         }
+        if let Some(post_commit_hook) = source.post_commit_hook.as_ref() {
+            self.post_commit_hook = Some(post_commit_hook.to_owned());
+        }
+        if let Some(pre_publish_hook) = source.pre_publish_hook.as_ref() {
+            self.pre_publish_hook = Some(pre_publish_hook.to_owned());
+        }
+        if let Some(post_publish_hook) = source.post_publish_hook.as_ref() {
+            self.post_publish_hook = Some(post_publish_hook.to_owned());
+        }
+        if let Some(post_tag_hook) = source.post_tag_hook.as_ref() {
+            self.post_tag_hook = Some(post_tag_hook.to_owned());
+        }
+        if let Some(post_push_hook) = source.post_push_hook.as_ref() {
+            self.post_push_hook = Some(post_push_hook.to_owned());
+        }
+        if let Some(workspace_pre_release_hook) = source.workspace_pre_release_hook.as_ref() {
+            self.workspace_pre_release_hook = Some(workspace_pre_release_hook.to_owned());
+        }
+        if let Some(changelog) = source.changelog.as_deref() {
+            self.changelog = Some(changelog.to_owned());
+        }
+        if let Some(post_release_version) = source.post_release_version.as_deref() {
+            self.post_release_version = Some(post_release_version.to_owned());
+        }
+        if let Some(post_release_commit_message) = source.post_release_commit_message.as_deref() {
+            self.post_release_commit_message = Some(post_release_commit_message.to_owned());
+        }
         if let Some(tag_message) = source.tag_message.as_deref() {
             self.tag_message = Some(tag_message.to_owned());
         }
@@ -172,6 +246,9 @@ impl Config {
             self.target = Some(target.to_owned());
         }
         self.rate_limit.update(&source.rate_limit);
+        if let Some(publish_jobs) = source.publish_jobs {
+            self.publish_jobs = Some(publish_jobs);
+        }
         if let Some(certs) = source.certs_source {
             self.certs_source = Some(certs);
         }
@@ -200,8 +277,29 @@ impl Config {
         self.push_remote.as_deref().unwrap_or("origin")
     }
 
+    /// The first of [`Self::registries`]
     pub fn registry(&self) -> Option<&str> {
-        self.registry.as_deref()
+        self.registries().into_iter().next().flatten()
+    }
+
+    /// The registries to publish to, `None` being the default registry
+    pub fn registries(&self) -> Vec<Option<&str>> {
+        let names = self
+            .registry
+            .as_ref()
+            .map(|r| r.names())
+            .unwrap_or_default();
+        if names.is_empty() {
+            return vec![None];
+        }
+        let mut registries = Vec::new();
+        for name in names {
+            let registry = (name != Registries::CRATES_IO).then_some(name);
+            if !registries.contains(&registry) {
+                registries.push(registry);
+            }
+        }
+        registries
     }
 
     pub fn release(&self) -> bool {
@@ -216,6 +314,20 @@ impl Config {
         self.verify.unwrap_or(true)
     }
 
+    pub fn publish_wait(&self) -> PublishWait {
+        self.publish_wait.unwrap_or_default()
+    }
+
+    /// Seconds to wait for a published version to appear in the index
+    pub fn publish_timeout(&self) -> u64 {
+        self.publish_timeout.unwrap_or(300)
+    }
+
+    /// How many times to retry `cargo publish` after a transient failure
+    pub fn publish_retries(&self) -> usize {
+        self.publish_retries.unwrap_or(0)
+    }
+
     pub fn owners(&self) -> &[String] {
         self.owners.as_ref().map(|v| v.as_ref()).unwrap_or(&[])
     }
@@ -262,6 +374,50 @@ impl Config {
         self.pre_release_hook.as_ref()
     }
 
+    pub fn post_commit_hook(&self) -> Option<&Command> {
+        self.post_commit_hook.as_ref()
+    }
+
+    pub fn pre_publish_hook(&self) -> Option<&Command> {
+        self.pre_publish_hook.as_ref()
+    }
+
+    pub fn post_publish_hook(&self) -> Option<&Command> {
+        self.post_publish_hook.as_ref()
+    }
+
+    pub fn post_tag_hook(&self) -> Option<&Command> {
+        self.post_tag_hook.as_ref()
+    }
+
+    pub fn post_push_hook(&self) -> Option<&Command> {
+        self.post_push_hook.as_ref()
+    }
+
+    pub fn workspace_pre_release_hook(&self) -> Option<&Command> {
+        self.workspace_pre_release_hook.as_ref()
+    }
+
+    pub fn changelog(&self) -> Option<&Path> {
+        self.changelog.as_deref()
+    }
+
+    pub fn post_release_version(&self) -> Option<&str> {
+        self.post_release_version.as_deref()
+    }
+
+    pub fn post_release_commit_message(&self) -> &str {
+        self.post_release_commit_message
+            .as_deref()
+            .unwrap_or_else(|| {
+                if self.consolidate_commits() {
+                    "chore: Start next development iteration"
+                } else {
+                    "chore: Start {{crate_name}} {{version}} development"
+                }
+            })
+    }
+
     pub fn tag_message(&self) -> &str {
         self.tag_message
             .as_deref()
@@ -311,6 +467,11 @@ impl Config {
         self.metadata.unwrap_or_default()
     }
 
+    /// How many crates to publish at once
+    pub fn publish_jobs(&self) -> usize {
+        self.publish_jobs.unwrap_or(1).max(1)
+    }
+
     pub fn certs_source(&self) -> CertsSource {
         self.certs_source.unwrap_or_default()
     }
@@ -361,13 +522,29 @@ impl From<Vec<UnstableValues>> for Unstable {
 #[serde(deny_unknown_fields)]
 pub struct Replace {
     pub file: PathBuf,
-    pub search: String,
+    /// Regex to search for, mutually exclusive with `key`
+    pub search: Option<String>,
+    /// Dotted path to a TOML, JSON, or YAML value, mutually exclusive with `search`
+    pub key: Option<String>,
     pub replace: String,
     pub min: Option<usize>,
     pub max: Option<usize>,
     pub exactly: Option<usize>,
     #[serde(default)]
     pub prerelease: bool,
+    #[serde(default)]
+    pub counts: ReplaceCounts,
+}
+
+/// What `min`, `max`, and `exactly` count when `file` is a glob
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ReplaceCounts {
+    /// Each matched file
+    #[default]
+    PerFile,
+    /// All matched files together
+    Total,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -386,6 +563,26 @@ impl Command {
     }
 }
 
+/// Registries, by the names Cargo gives them, like in the manifest's `publish` field
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Registries {
+    Name(String),
+    Names(Vec<String>),
+}
+
+impl Registries {
+    /// The name Cargo gives the default registry
+    pub const CRATES_IO: &'static str = "crates-io";
+
+    pub fn names(&self) -> Vec<&str> {
+        match self {
+            Self::Name(s) => vec![s.as_str()],
+            Self::Names(n) => n.iter().map(|s| s.as_str()).collect(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
 #[serde(rename_all = "kebab-case")]
 #[value(rename_all = "kebab-case")]
@@ -410,6 +607,18 @@ pub enum CertsSource {
     Native,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+#[value(rename_all = "kebab-case")]
+#[derive(Default)]
+pub enum PublishWait {
+    /// Rely on `cargo publish` waiting for the index
+    #[default]
+    Cargo,
+    /// Poll the index until the published version appears
+    Index,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
 #[serde(rename_all = "kebab-case")]
 #[value(rename_all = "kebab-case")]
@@ -527,6 +736,9 @@ pub struct RateLimit {
     pub new_packages: Option<usize>,
     #[serde(default)]
     pub existing_packages: Option<usize>,
+    /// Wait to stay within the limits rather than failing
+    #[serde(default)]
+    pub throttle: Option<bool>,
 }
 
 impl RateLimit {
@@ -538,6 +750,7 @@ impl RateLimit {
         Self {
             new_packages: Some(5),
             existing_packages: Some(30),
+            throttle: Some(false),
         }
     }
 
@@ -548,6 +761,9 @@ impl RateLimit {
         if source.existing_packages.is_some() {
             self.existing_packages = source.existing_packages;
         }
+        if source.throttle.is_some() {
+            self.throttle = source.throttle;
+        }
     }
 
     pub fn new_packages(&self) -> usize {
@@ -557,44 +773,28 @@ impl RateLimit {
     pub fn existing_packages(&self) -> usize {
         self.existing_packages.unwrap_or(30)
     }
+
+    pub fn throttle(&self) -> bool {
+        self.throttle.unwrap_or(false)
+    }
 }
 
 pub fn load_workspace_config(
     args: &ConfigArgs,
     ws_meta: &cargo_metadata::Metadata,
 ) -> CargoResult<Config> {
+    let layers = workspace_config_layers(args, ws_meta)?;
     let mut release_config = Config {
         is_workspace: 1 < ws_meta.workspace_members.len(),
         ..Default::default()
     };
-
-    if !args.isolated {
-        let is_workspace = 1 < ws_meta.workspace_members.len();
-        let cfg = if is_workspace {
-            resolve_workspace_config(ws_meta.workspace_root.as_std_path())?
-        } else {
-            // Outside of workspaces, go ahead and treat package config as workspace config so
-            // users don't have to specially configure workspace-specific fields
-            let pkg = ws_meta
-                .packages
-                .iter()
-                .find(|p| ws_meta.workspace_members.contains(&p.id))
-                .unwrap();
-            resolve_config(
-                ws_meta.workspace_root.as_std_path(),
-                pkg.manifest_path.as_std_path(),
-            )?
-        };
-        release_config.update(&cfg);
+    for layer in &layers {
+        release_config.update(&layer.config);
     }
 
-    if let Some(custom_config_path) = args.custom_config.as_ref() {
-        // when calling with -c option
-        let cfg = resolve_custom_config(custom_config_path.as_ref())?.unwrap_or_default();
-        release_config.update(&cfg);
-    }
+    check_pins(ws_meta.workspace_root.as_std_path(), &layers)?;
+    validate_workspace_templates(&release_config, &layers)?;
 
-    release_config.update(&args.to_config());
     Ok(release_config)
 }
 
@@ -603,31 +803,1032 @@ pub fn load_package_config(
     ws_meta: &cargo_metadata::Metadata,
     pkg: &cargo_metadata::Package,
 ) -> CargoResult<Config> {
-    let manifest_path = pkg.manifest_path.as_std_path();
-
-    let is_workspace = 1 < ws_meta.workspace_members.len();
+    let layers = package_config_layers(args, ws_meta, pkg)?;
     let mut release_config = Config {
-        is_workspace,
+        is_workspace: 1 < ws_meta.workspace_members.len(),
         ..Default::default()
     };
+    for layer in &layers {
+        release_config.update(&layer.config);
+    }
+    if let Some(allowed) = pkg.publish.as_ref().filter(|a| !a.is_empty()) {
+        resolve_registries(&release_config, &pkg.name, allowed)?;
+    }
 
-    if !args.isolated {
-        let cfg = resolve_config(ws_meta.workspace_root.as_std_path(), manifest_path)?;
-        release_config.update(&cfg);
+    check_pins(ws_meta.workspace_root.as_std_path(), &layers)?;
+    validate_package_templates(&release_config, &layers)?;
+
+    Ok(release_config)
+}
+
+/// The registry implied by the manifest's `publish` field
+///
+/// `publish` is an allow-list, so the package is only published to the sole registry allowed, like
+/// `cargo publish`, and otherwise to the default registry.
+fn publish_registry(allowed: &[String]) -> Option<Registries> {
+    match allowed {
+        [registry] => Some(Registries::Name(registry.clone())),
+        _ => None,
     }
+}
 
-    if let Some(custom_config_path) = args.custom_config.as_ref() {
-        // when calling with -c option
-        let cfg = resolve_custom_config(Path::new(custom_config_path))?.unwrap_or_default();
-        release_config.update(&cfg);
+/// Check `registry` against the registries the manifest's `publish` field allows
+fn resolve_registries(config: &Config, name: &str, allowed: &[String]) -> CargoResult<()> {
+    if !config.publish() {
+        return Ok(());
+    }
+    for registry in config.registries() {
+        let registry = registry.unwrap_or(Registries::CRATES_IO);
+        if !allowed.iter().any(|a| a == registry) {
+            anyhow::bail!(
+                "`{name}` cannot be published to `{registry}`, its `publish` field only allows {}",
+                allowed
+                    .iter()
+                    .map(|a| format!("`{a}`"))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+        }
     }
+    Ok(())
+}
 
-    release_config.update(&args.to_config());
+/// The layers merged by [`load_workspace_config`], lowest precedence first
+pub fn workspace_config_layers(
+    args: &ConfigArgs,
+    ws_meta: &cargo_metadata::Metadata,
+) -> CargoResult<Vec<ConfigLayer>> {
+    let is_workspace = 1 < ws_meta.workspace_members.len();
+    // Outside of workspaces, go ahead and treat package config as workspace config so
+    // users don't have to specially configure workspace-specific fields
+    let pkg = (!is_workspace).then(|| {
+        ws_meta
+            .packages
+            .iter()
+            .find(|p| ws_meta.workspace_members.contains(&p.id))
+            .unwrap()
+    });
+    let manifest_path = pkg.map(|p| p.manifest_path.as_std_path());
+    let package = pkg.map(|p| p.name.as_str());
+    let sources = config_sources(args, ws_meta.workspace_root.as_std_path(), manifest_path);
+    let mut layers = file_layers(args.profile.as_deref(), package, sources)?;
+    if let Some(profile) = args.profile.as_deref()
+        && !has_profile(&layers, profile)
+    {
+        // The profile may only be defined for some packages
+        let mut defined = false;
+        for pkg in ws_meta.workspace_packages() {
+            let sources = package_sources(
+                ws_meta.workspace_root.as_std_path(),
+                pkg.manifest_path.as_std_path(),
+            );
+            if has_profile(
+                &file_layers(Some(profile), Some(&pkg.name), sources.into())?,
+                profile,
+            ) {
+                defined = true;
+                break;
+            }
+        }
+        if !defined {
+            anyhow::bail!("profile `{profile}` is not defined in any release config");
+        }
+    }
+    if let Some(config) = env_config()? {
+        layers.push(ConfigLayer {
+            origin: ConfigOrigin::Env,
+            config,
+        });
+    }
+    layers.push(ConfigLayer {
+        origin: ConfigOrigin::Cli,
+        config: args.to_config(),
+    });
+    Ok(layers)
+}
 
-    let overrides = resolve_overrides(ws_meta.workspace_root.as_std_path(), manifest_path)?;
-    release_config.update(&overrides);
+/// The layers merged by [`load_package_config`], lowest precedence first
+pub fn package_config_layers(
+    args: &ConfigArgs,
+    ws_meta: &cargo_metadata::Metadata,
+    pkg: &cargo_metadata::Package,
+) -> CargoResult<Vec<ConfigLayer>> {
+    let manifest_path = pkg.manifest_path.as_std_path();
 
-    Ok(release_config)
+    let sources = config_sources(
+        args,
+        ws_meta.workspace_root.as_std_path(),
+        Some(manifest_path),
+    );
+    let mut layers = file_layers(args.profile.as_deref(), Some(&pkg.name), sources)?;
+    if let Some(config) = env_config()? {
+        layers.push(ConfigLayer {
+            origin: ConfigOrigin::Env,
+            config,
+        });
+    }
+    layers.push(ConfigLayer {
+        origin: ConfigOrigin::Cli,
+        config: args.to_config(),
+    });
+    let mut manifest_config =
+        resolve_overrides(ws_meta.workspace_root.as_std_path(), manifest_path)?;
+    if layers.iter().all(|l| l.config.registry.is_none()) {
+        manifest_config.registry = pkg.publish.as_deref().and_then(publish_registry);
+    }
+    layers.push(ConfigLayer {
+        origin: ConfigOrigin::Manifest(manifest_path.to_owned()),
+        config: manifest_config,
+    });
+    Ok(layers)
+}
+
+/// Load each source, followed by the selected profile from each source
+///
+/// `[package.<pattern>]` tables matching `package` from workspace-wide sources are layered
+/// between the workspace and package sources.
+/// Profiles take precedence over all files so a workspace profile can override package settings.
+fn file_layers(
+    profile: Option<&str>,
+    package: Option<&str>,
+    sources: Vec<ConfigSource>,
+) -> CargoResult<Vec<ConfigLayer>> {
+    let mut layers = Vec::new();
+    let mut package_layers = Vec::new();
+    let mut profile_layers = Vec::new();
+    for source in sources {
+        let scope = source.scope;
+        if scope == ConfigScope::Package {
+            layers.append(&mut package_layers);
+        }
+        push_file_layers(
+            source,
+            profile,
+            package,
+            &mut FileLayers {
+                layers: &mut layers,
+                package_layers: &mut package_layers,
+                profile_layers: &mut profile_layers,
+            },
+            &mut Vec::new(),
+        )?;
+        if scope == ConfigScope::Custom {
+            layers.append(&mut package_layers);
+        }
+    }
+    layers.append(&mut package_layers);
+    layers.extend(profile_layers);
+    Ok(layers)
+}
+
+struct FileLayers<'l> {
+    layers: &'l mut Vec<ConfigLayer>,
+    package_layers: &'l mut Vec<ConfigLayer>,
+    profile_layers: &'l mut Vec<ConfigLayer>,
+}
+
+fn push_file_layers(
+    source: ConfigSource,
+    profile: Option<&str>,
+    package: Option<&str>,
+    out: &mut FileLayers<'_>,
+    extending: &mut Vec<PathBuf>,
+) -> CargoResult<()> {
+    let Some(mut config) = source.load()? else {
+        return Ok(());
+    };
+
+    // Inherited settings come first so the extending file takes precedence
+    if let Some(extends) = config.extends.take() {
+        let base_dir = source.path.parent().unwrap_or_else(|| Path::new("."));
+        extending.push(dunce::canonicalize(&source.path).unwrap_or_else(|_| source.path.clone()));
+        for entry in extends {
+            let path = base_dir.join(entry.strip_prefix("file://").unwrap_or(&entry));
+            if !path.exists() {
+                anyhow::bail!(
+                    "unable to find `{entry}`, extended by `{}`",
+                    source.path.display()
+                );
+            }
+            let path = dunce::canonicalize(&path)?;
+            if extending.contains(&path) {
+                anyhow::bail!(
+                    "`{}` extends itself through `{entry}`",
+                    source.path.display()
+                );
+            }
+            let base = ConfigSource {
+                extended_by: Some(source.path.clone()),
+                ..ConfigSource::new(path, &[], source.scope)
+            };
+            push_file_layers(base, profile, package, out, extending)?;
+        }
+        extending.pop();
+    }
+
+    let packages = config.package.take().unwrap_or_default();
+    if !packages.is_empty() && source.scope == ConfigScope::Package {
+        anyhow::bail!(
+            "`package` tables are only supported in workspace config, found in `{}`",
+            source.path.display()
+        );
+    }
+    if let Some(name) = package {
+        for (pattern, package) in matching_packages(&packages, name)? {
+            if package.profile.is_some() || package.extends.is_some() || package.package.is_some() {
+                anyhow::bail!(
+                    "package `{pattern}` in `{}` cannot contain `profile`, `extends`, or `package`",
+                    source.path.display()
+                );
+            }
+            out.package_layers.push(ConfigLayer {
+                origin: ConfigOrigin::File(ConfigSource {
+                    package: Some(pattern.to_owned()),
+                    ..source.clone()
+                }),
+                config: package.clone(),
+            });
+        }
+    }
+
+    let profiles = config.profile.take().unwrap_or_default();
+    if let Some(name) = profile
+        && let Some(profile) = profiles.get(name)
+    {
+        if profile.profile.is_some() || profile.extends.is_some() || profile.package.is_some() {
+            anyhow::bail!(
+                "profile `{name}` in `{}` cannot contain `profile`, `extends`, or `package`",
+                source.path.display()
+            );
+        }
+        out.profile_layers.push(ConfigLayer {
+            origin: ConfigOrigin::File(ConfigSource {
+                profile: Some(name.to_owned()),
+                ..source.clone()
+            }),
+            config: profile.clone(),
+        });
+    }
+    out.layers.push(ConfigLayer {
+        origin: ConfigOrigin::File(source),
+        config,
+    });
+    Ok(())
+}
+
+/// The `[package.<pattern>]` tables that apply to `name`, with exact names last to take precedence
+fn matching_packages<'c>(
+    tables: &'c std::collections::BTreeMap<String, Config>,
+    name: &str,
+) -> CargoResult<Vec<(&'c str, &'c Config)>> {
+    let mut globs = Vec::new();
+    let mut exact = Vec::new();
+    for (pattern, config) in tables {
+        if pattern == name {
+            exact.push((pattern.as_str(), config));
+        } else if globset::Glob::new(pattern)
+            .with_context(|| format!("invalid package pattern `{pattern}`"))?
+            .compile_matcher()
+            .is_match(name)
+        {
+            globs.push((pattern.as_str(), config));
+        }
+    }
+    globs.extend(exact);
+    Ok(globs)
+}
+
+fn has_profile(layers: &[ConfigLayer], name: &str) -> bool {
+    layers.iter().any(|l| {
+        matches!(&l.origin, ConfigOrigin::File(source) if source.profile.as_deref() == Some(name))
+    })
+}
+
+/// Records the content of each config inherited through `extends`
+pub const LOCK_FILE: &str = "release.lock";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct ConfigLock {
+    /// Git blob id of each inherited file, by path relative to the workspace root
+    #[serde(default)]
+    extends: std::collections::BTreeMap<String, String>,
+}
+
+fn extended_sources(layers: &[ConfigLayer]) -> impl Iterator<Item = &ConfigSource> {
+    layers.iter().filter_map(|l| match &l.origin {
+        ConfigOrigin::File(source) if source.extended_by.is_some() && source.profile.is_none() => {
+            Some(source)
+        }
+        _ => None,
+    })
+}
+
+fn lock_key(workspace_root: &Path, path: &Path) -> String {
+    let root = workspace_root.components().collect::<Vec<_>>();
+    let path = path.components().collect::<Vec<_>>();
+    let common = root.iter().zip(&path).take_while(|(r, p)| r == p).count();
+    let mut key = Vec::new();
+    if common == 0 {
+        // Different drives, nothing to be relative to
+        key.extend(path.iter().map(|c| c.as_os_str().to_string_lossy()));
+    } else {
+        key.extend(root[common..].iter().map(|_| "..".into()));
+        key.extend(
+            path[common..]
+                .iter()
+                .map(|c| c.as_os_str().to_string_lossy()),
+        );
+    }
+    key.join("/")
+}
+
+fn content_id(path: &Path) -> CargoResult<String> {
+    let content = std::fs::read(path)?;
+    let id = git2::Oid::hash_object(git2::ObjectType::Blob, &content)?;
+    Ok(id.to_string())
+}
+
+/// Report inherited configs that changed since they were pinned in [`LOCK_FILE`]
+fn check_pins(workspace_root: &Path, layers: &[ConfigLayer]) -> CargoResult<()> {
+    let lock_path = workspace_root.join(LOCK_FILE);
+    if !lock_path.exists() {
+        return Ok(());
+    }
+    let lock: ConfigLock = toml::from_str(&std::fs::read_to_string(&lock_path)?)
+        .with_context(|| format!("Failed to parse `{}`", lock_path.display()))?;
+    for source in extended_sources(layers) {
+        let key = lock_key(workspace_root, &source.path);
+        match lock.extends.get(&key) {
+            Some(id) if *id == content_id(&source.path)? => {}
+            Some(_) => anyhow::bail!(
+                "`{key}` changed since it was pinned in `{LOCK_FILE}`; review it and run `cargo release config --pin` to accept the change"
+            ),
+            None => anyhow::bail!(
+                "`{key}` is not pinned in `{LOCK_FILE}`; run `cargo release config --pin` to pin it"
+            ),
+        }
+    }
+    Ok(())
+}
+
+/// Pin the current content of the configs inherited by `layers`, returning how many were pinned
+pub fn pin_extends(workspace_root: &Path, layers: &[ConfigLayer]) -> CargoResult<usize> {
+    let mut lock = ConfigLock::default();
+    for source in extended_sources(layers) {
+        lock.extends.insert(
+            lock_key(workspace_root, &source.path),
+            content_id(&source.path)?,
+        );
+    }
+    let lock_path = workspace_root.join(LOCK_FILE);
+    let content = format!(
+        "# Pins the configs inherited through `extends`, see `cargo release config --pin`\n{}",
+        toml::to_string(&lock)?
+    );
+    std::fs::write(&lock_path, content)?;
+    Ok(lock.extends.len())
+}
+
+/// Prefix of the environment variables that override config keys
+const ENV_PREFIX: &str = "CARGO_RELEASE_";
+
+/// The environment variable overriding `key`, like `CARGO_RELEASE_RATE_LIMIT_NEW_PACKAGES`
+pub fn env_var(key: &[&str]) -> String {
+    format!(
+        "{ENV_PREFIX}{}",
+        key.join("_").replace('-', "_").to_uppercase()
+    )
+}
+
+/// Config keys that can be overridden from the environment
+fn env_keys() -> Vec<Vec<&'static str>> {
+    let nested = [
+        ("unstable", serde_fields::<Unstable>()),
+        ("rate-limit", serde_fields::<RateLimit>()),
+    ];
+    let mut keys = Vec::new();
+    for field in serde_fields::<Config>() {
+        if let Some((_, fields)) = nested.iter().find(|(name, _)| name == field) {
+            keys.extend(fields.iter().map(|f| vec![*field, *f]));
+        } else if !["extends", "profile", "package"].contains(field) {
+            keys.push(vec![*field]);
+        }
+    }
+    keys
+}
+
+/// The serde names of `T`'s fields
+fn serde_fields<'de, T: Deserialize<'de>>() -> &'static [&'static str] {
+    struct FieldsDeserializer<'f>(&'f mut &'static [&'static str]);
+
+    impl<'de> serde::Deserializer<'de> for FieldsDeserializer<'_> {
+        type Error = serde::de::value::Error;
+
+        fn deserialize_any<V: serde::de::Visitor<'de>>(
+            self,
+            _visitor: V,
+        ) -> Result<V::Value, Self::Error> {
+            Err(serde::de::Error::custom("expected a struct"))
+        }
+
+        fn deserialize_struct<V: serde::de::Visitor<'de>>(
+            self,
+            _name: &'static str,
+            fields: &'static [&'static str],
+            _visitor: V,
+        ) -> Result<V::Value, Self::Error> {
+            *self.0 = fields;
+            Err(serde::de::Error::custom("only collecting fields"))
+        }
+
+        serde::forward_to_deserialize_any! {
+            bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf
+            option unit unit_struct newtype_struct seq tuple tuple_struct map enum identifier
+            ignored_any
+        }
+    }
+
+    let mut fields: &'static [&'static str] = &[];
+    let _ = T::deserialize(FieldsDeserializer(&mut fields));
+    fields
+}
+
+/// Read config overrides from `CARGO_RELEASE_<KEY>` environment variables
+fn env_config() -> CargoResult<Option<Config>> {
+    let mut table = toml::Table::new();
+    for key in env_keys() {
+        let var = env_var(&key);
+        let Ok(raw) = std::env::var(&var) else {
+            continue;
+        };
+        let value = parse_env_value(&key, &raw)
+            .map_err(|err| anyhow::format_err!("invalid `{var}`: {}", err.message()))?;
+        insert_key(&mut table, &key, value);
+    }
+    if table.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(table.try_into()?))
+}
+
+/// Interpret `raw` as a TOML value, falling back to a string or a comma-separated list
+fn parse_env_value(key: &[&str], raw: &str) -> Result<toml::Value, toml::de::Error> {
+    let mut candidates = Vec::new();
+    if let Ok(mut table) = toml::from_str::<toml::Table>(&format!("value = {raw}"))
+        && let Some(value) = table.remove("value")
+    {
+        candidates.push(value);
+    }
+    candidates.push(toml::Value::String(raw.to_owned()));
+    candidates.push(toml::Value::Array(
+        raw.split(',')
+            .map(|s| s.trim())
+            .filter(|s| !s.is_empty())
+            .map(|s| toml::Value::String(s.to_owned()))
+            .collect(),
+    ));
+
+    let mut first_err = None;
+    for candidate in candidates {
+        let mut table = toml::Table::new();
+        insert_key(&mut table, key, candidate.clone());
+        match table.try_into::<Config>() {
+            Ok(_) => return Ok(candidate),
+            Err(err) => {
+                first_err.get_or_insert(err);
+            }
+        }
+    }
+    Err(first_err.expect("always a candidate"))
+}
+
+fn insert_key(table: &mut toml::Table, key: &[&str], value: toml::Value) {
+    let (last, parents) = key.split_last().expect("keys are non-empty");
+    let mut table = table;
+    for parent in parents {
+        table = table
+            .entry(*parent)
+            .or_insert_with(|| toml::Value::Table(Default::default()))
+            .as_table_mut()
+            .expect("only tables are nested");
+    }
+    table.insert((*last).to_owned(), value);
+}
+
+/// Settings from one origin, merged with [`Config::update`]
+#[derive(Clone, Debug)]
+pub struct ConfigLayer {
+    pub origin: ConfigOrigin,
+    pub config: Config,
+}
+
+/// Where a [`ConfigLayer`] came from
+#[derive(Clone, Debug)]
+pub enum ConfigOrigin {
+    /// Built-in defaults
+    Default,
+    /// A config file or manifest table
+    File(ConfigSource),
+    /// `CARGO_RELEASE_<KEY>` environment variables
+    Env,
+    /// Command-line arguments
+    Cli,
+    /// Implied by other fields in a package's `Cargo.toml`, like `publish = false`
+    Manifest(PathBuf),
+}
+
+const TAG_PREFIX_VARIABLES: &[&str] = &[
+    "prev_version",
+    "prev_metadata",
+    "version",
+    "metadata",
+    "crate_name",
+];
+const TAG_NAME_VARIABLES: &[&str] = &[
+    "prev_version",
+    "prev_metadata",
+    "version",
+    "metadata",
+    "crate_name",
+    "prefix",
+];
+const TAG_MESSAGE_VARIABLES: &[&str] = &[
+    "prev_version",
+    "prev_metadata",
+    "version",
+    "metadata",
+    "crate_name",
+    "date",
+    "tag_name",
+    "branch",
+    "head_sha",
+    "prev_tag",
+    "registry",
+];
+const PACKAGE_COMMIT_VARIABLES: &[&str] = &[
+    "prev_version",
+    "prev_metadata",
+    "version",
+    "metadata",
+    "crate_name",
+    "date",
+    "branch",
+    "head_sha",
+    "prev_tag",
+    "registry",
+];
+const WORKSPACE_COMMIT_VARIABLES: &[&str] = &["version", "metadata", "date", "branch", "head_sha"];
+const REPLACE_VARIABLES: &[&str] = &[
+    "prev_version",
+    "prev_metadata",
+    "version",
+    "metadata",
+    "crate_name",
+    "repository",
+    "date",
+    "tag_name",
+    "branch",
+    "head_sha",
+    "prev_tag",
+    "registry",
+];
+const HOOK_VARIABLES: &[&str] = &[
+    "prev_version",
+    "prev_metadata",
+    "version",
+    "metadata",
+    "crate_name",
+    "date",
+    "tag_name",
+    "branch",
+    "head_sha",
+    "prev_tag",
+    "registry",
+];
+const WORKSPACE_HOOK_VARIABLES: &[&str] = &["date", "branch", "head_sha"];
+const POST_RELEASE_VERSION_VARIABLES: &[&str] =
+    &["version", "next_major", "next_minor", "next_patch"];
+
+/// A step in the path to a setting within a config file
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum KeyPart<'k> {
+    Key(&'k str),
+    Index(usize),
+}
+
+/// A template setting to validate
+struct TemplateField<'c> {
+    key: Vec<KeyPart<'static>>,
+    template: &'c str,
+    available: &'static [&'static str],
+    context: Option<&'static str>,
+}
+
+impl<'c> TemplateField<'c> {
+    fn new(key: &'static str, template: &'c str, available: &'static [&'static str]) -> Self {
+        Self {
+            key: vec![KeyPart::Key(key)],
+            template,
+            available,
+            context: None,
+        }
+    }
+
+    fn name(&self) -> String {
+        let mut name = String::new();
+        for part in &self.key {
+            match part {
+                KeyPart::Key(key) => {
+                    if !name.is_empty() {
+                        name.push('.');
+                    }
+                    name.push_str(key);
+                }
+                KeyPart::Index(index) => {
+                    name.push_str(&format!("[{index}]"));
+                }
+            }
+        }
+        name
+    }
+}
+
+fn command_fields<'c>(
+    key: &'static str,
+    command: Option<&'c Command>,
+    available: &'static [&'static str],
+) -> Vec<TemplateField<'c>> {
+    let Some(command) = command else {
+        return Vec::new();
+    };
+    command
+        .args()
+        .into_iter()
+        .enumerate()
+        .map(|(i, template)| TemplateField {
+            key: vec![KeyPart::Key(key), KeyPart::Index(i)],
+            template,
+            available,
+            context: None,
+        })
+        .collect()
+}
+
+fn validate_workspace_templates(config: &Config, layers: &[ConfigLayer]) -> CargoResult<()> {
+    let mut fields = Vec::new();
+    if config.consolidate_commits() {
+        for (key, template) in [
+            (
+                "pre-release-commit-message",
+                config.pre_release_commit_message(),
+            ),
+            (
+                "post-release-commit-message",
+                config.post_release_commit_message(),
+            ),
+        ] {
+            fields.push(TemplateField {
+                context: Some("a consolidated commit covers all crates"),
+                ..TemplateField::new(key, template, WORKSPACE_COMMIT_VARIABLES)
+            });
+        }
+    }
+    fields.extend(command_fields(
+        "workspace-pre-release-hook",
+        config.workspace_pre_release_hook(),
+        WORKSPACE_HOOK_VARIABLES,
+    ));
+    validate_templates(&fields, layers)
+}
+
+fn validate_package_templates(config: &Config, layers: &[ConfigLayer]) -> CargoResult<()> {
+    let mut fields = Vec::new();
+    if let Some(tag_prefix) = config.tag_prefix.as_deref() {
+        fields.push(TemplateField::new(
+            "tag-prefix",
+            tag_prefix,
+            TAG_PREFIX_VARIABLES,
+        ));
+    }
+    fields.push(TemplateField::new(
+        "tag-name",
+        config.tag_name(),
+        TAG_NAME_VARIABLES,
+    ));
+    fields.push(TemplateField::new(
+        "tag-message",
+        config.tag_message(),
+        TAG_MESSAGE_VARIABLES,
+    ));
+    if !config.consolidate_commits() {
+        fields.push(TemplateField::new(
+            "pre-release-commit-message",
+            config.pre_release_commit_message(),
+            PACKAGE_COMMIT_VARIABLES,
+        ));
+        fields.push(TemplateField::new(
+            "post-release-commit-message",
+            config.post_release_commit_message(),
+            PACKAGE_COMMIT_VARIABLES,
+        ));
+    }
+    if let Some(post_release_version) = config
+        .post_release_version()
+        .filter(|v| v.parse::<crate::steps::BumpLevel>().is_err())
+    {
+        fields.push(TemplateField::new(
+            "post-release-version",
+            post_release_version,
+            POST_RELEASE_VERSION_VARIABLES,
+        ));
+    }
+    for (i, replace) in config.pre_release_replacements().iter().enumerate() {
+        fields.push(TemplateField {
+            key: vec![
+                KeyPart::Key("pre-release-replacements"),
+                KeyPart::Index(i),
+                KeyPart::Key("replace"),
+            ],
+            template: &replace.replace,
+            available: REPLACE_VARIABLES,
+            context: None,
+        });
+    }
+    for (key, command) in [
+        ("pre-release-hook", config.pre_release_hook()),
+        ("post-commit-hook", config.post_commit_hook()),
+        ("pre-publish-hook", config.pre_publish_hook()),
+        ("post-publish-hook", config.post_publish_hook()),
+        ("post-tag-hook", config.post_tag_hook()),
+        ("post-push-hook", config.post_push_hook()),
+    ] {
+        fields.extend(command_fields(key, command, HOOK_VARIABLES));
+    }
+    validate_templates(&fields, layers)
+}
+
+fn validate_templates(fields: &[TemplateField<'_>], layers: &[ConfigLayer]) -> CargoResult<()> {
+    for field in fields {
+        let Err(err) = crate::ops::template::validate(field.template, field.available) else {
+            continue;
+        };
+        let title = format!("invalid template in `{}`", field.name());
+        let available = field
+            .available
+            .iter()
+            .map(|v| format!("`{{{{{v}}}}}`"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let help = if let Some(context) = field.context {
+            format!("{context}; available placeholders: {available}")
+        } else {
+            format!("available placeholders: {available}")
+        };
+
+        let Some(definition) = find_definition(layers, &field.key, field.template) else {
+            anyhow::bail!("{title}: {} in `{}`\n{help}", err.message, field.template);
+        };
+        let span = definition.template_span(field.template, err.span.clone());
+        let path = definition.path.display().to_string();
+        let report = &[
+            annotate_snippets::Group::with_title(
+                annotate_snippets::Level::ERROR.primary_title(title),
+            )
+            .element(
+                annotate_snippets::Snippet::source(definition.source.as_str())
+                    .path(path)
+                    .annotation(
+                        annotate_snippets::AnnotationKind::Primary
+                            .span(span)
+                            .label(err.message.as_str()),
+                    ),
+            ),
+            annotate_snippets::Group::with_title(
+                annotate_snippets::Level::HELP.secondary_title(help),
+            ),
+        ];
+        let _ = crate::ops::shell::print_report(report);
+        return Err(crate::error::Reported.into());
+    }
+    Ok(())
+}
+
+/// A file that settings may be read from
+#[derive(Clone, Debug)]
+pub struct ConfigSource {
+    pub path: PathBuf,
+    /// The table within the file holding the settings
+    pub table: &'static [&'static str],
+    /// The `[profile.<name>]` within [`ConfigSource::table`] holding the settings
+    pub profile: Option<String>,
+    /// The `[package.<pattern>]` within [`ConfigSource::table`] holding the settings
+    pub package: Option<String>,
+    /// The file that inherits this one through `extends`
+    pub extended_by: Option<PathBuf>,
+    pub scope: ConfigScope,
+}
+
+impl ConfigSource {
+    fn new(path: PathBuf, table: &'static [&'static str], scope: ConfigScope) -> Self {
+        Self {
+            path,
+            table,
+            profile: None,
+            package: None,
+            extended_by: None,
+            scope,
+        }
+    }
+
+    fn load(&self) -> CargoResult<Option<Config>> {
+        match self.table {
+            ["workspace", ..] => get_ws_config_from_manifest(&self.path),
+            ["package", ..] => get_pkg_config_from_manifest(&self.path),
+            _ => get_config_from_file(&self.path),
+        }
+    }
+
+    /// The 1-based line `key` is set on, if any
+    pub fn line_of(&self, key: &[&str]) -> Option<usize> {
+        let text = std::fs::read_to_string(&self.path).ok()?;
+        let doc = text.parse::<toml_edit::Document<String>>().ok()?;
+        let key = key.iter().map(|k| KeyPart::Key(k)).collect::<Vec<_>>();
+        let span = find_value(doc.as_item(), self, &key)?.span()?;
+        Some(text[..span.start].matches('\n').count() + 1)
+    }
+}
+
+/// How widely a [`ConfigSource`] applies
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ConfigScope {
+    /// Shared by all packages, like the workspace `release.toml`
+    Workspace,
+    /// Specific to one package, like its `Cargo.toml`
+    Package,
+    /// Given with `--config`
+    Custom,
+}
+
+/// The files settings are read from, lowest precedence first
+fn config_sources(
+    args: &ConfigArgs,
+    workspace_root: &Path,
+    manifest_path: Option<&Path>,
+) -> Vec<ConfigSource> {
+    let mut sources = Vec::new();
+    if !args.isolated {
+        if let Some(home) = dirs_next::home_dir() {
+            sources.push(ConfigSource::new(
+                home.join(".release.toml"),
+                &[],
+                ConfigScope::Workspace,
+            ));
+        }
+        if let Some(config_dir) = dirs_next::config_dir() {
+            sources.push(ConfigSource::new(
+                config_dir.join("cargo-release/release.toml"),
+                &[],
+                ConfigScope::Workspace,
+            ));
+        }
+        sources.push(ConfigSource::new(
+            workspace_root.join("release.toml"),
+            &[],
+            ConfigScope::Workspace,
+        ));
+        sources.push(ConfigSource::new(
+            workspace_root.join("Cargo.toml"),
+            &["workspace", "metadata", "release"],
+            ConfigScope::Workspace,
+        ));
+        if let Some(manifest_path) = manifest_path {
+            sources.extend(package_sources(workspace_root, manifest_path));
+        }
+    }
+    if let Some(custom_config) = args.custom_config.as_ref() {
+        sources.push(ConfigSource::new(
+            custom_config.clone(),
+            &[],
+            ConfigScope::Custom,
+        ));
+    }
+    sources
+}
+
+/// The files specific to the package at `manifest_path`, lowest precedence first
+fn package_sources(workspace_root: &Path, manifest_path: &Path) -> [ConfigSource; 2] {
+    let crate_root = manifest_path.parent().unwrap_or_else(|| Path::new("."));
+    // The root package shares its `release.toml` with the workspace
+    let scope = if crate_root == workspace_root {
+        ConfigScope::Workspace
+    } else {
+        ConfigScope::Package
+    };
+    [
+        ConfigSource::new(crate_root.join("release.toml"), &[], scope),
+        ConfigSource::new(
+            manifest_path.to_owned(),
+            &["package", "metadata", "release"],
+            ConfigScope::Package,
+        ),
+    ]
+}
+
+/// Where a setting's value was written
+struct Definition {
+    path: PathBuf,
+    source: String,
+    /// Byte range of the value, including quotes
+    span: std::ops::Range<usize>,
+}
+
+impl Definition {
+    /// Map `span` within `template` to a span within the file
+    fn template_span(
+        &self,
+        template: &str,
+        span: std::ops::Range<usize>,
+    ) -> std::ops::Range<usize> {
+        let raw = &self.source[self.span.clone()];
+        for quote in [r#"""""#, "'''", r#"""#, "'"] {
+            let Some(inner) = raw.strip_prefix(quote).and_then(|r| r.strip_suffix(quote)) else {
+                continue;
+            };
+            // Multi-line strings trim a leading newline
+            let offset = if inner == template {
+                quote.len()
+            } else if inner.strip_prefix('\n') == Some(template) {
+                quote.len() + 1
+            } else {
+                // With escapes, point to the whole value
+                break;
+            };
+            let start = self.span.start + offset;
+            return start + span.start..start + span.end;
+        }
+        self.span.clone()
+    }
+}
+
+/// Find the highest-precedence file that sets `key` to `template`
+fn find_definition(
+    layers: &[ConfigLayer],
+    key: &[KeyPart<'_>],
+    template: &str,
+) -> Option<Definition> {
+    let sources = layers.iter().filter_map(|l| match &l.origin {
+        ConfigOrigin::File(source) => Some(source),
+        _ => None,
+    });
+    for source in sources.rev() {
+        let Ok(text) = std::fs::read_to_string(&source.path) else {
+            continue;
+        };
+        let Ok(doc) = text.parse::<toml_edit::Document<String>>() else {
+            continue;
+        };
+        let Some(value) = find_value(doc.as_item(), source, key) else {
+            continue;
+        };
+        if value.as_str() != Some(template) {
+            // Overridden elsewhere, like on the command-line
+            return None;
+        }
+        let span = value.span()?;
+        return Some(Definition {
+            path: source.path.clone(),
+            source: text,
+            span,
+        });
+    }
+    None
+}
+
+fn find_value<'d>(
+    item: &'d toml_edit::Item,
+    source: &ConfigSource,
+    key: &[KeyPart<'_>],
+) -> Option<&'d toml_edit::Value> {
+    let mut item = item;
+    for name in source.table {
+        item = item.get(name)?;
+    }
+    if let Some(package) = source.package.as_deref() {
+        item = item.get("package")?.get(package)?;
+    }
+    if let Some(profile) = source.profile.as_deref() {
+        item = item.get("profile")?.get(profile)?;
+    }
+    for (i, part) in key.iter().enumerate() {
+        item = match part {
+            KeyPart::Key(key) => item.get(key)?,
+            // A command written as a single string
+            KeyPart::Index(0) if item.is_str() && i + 1 == key.len() => item,
+            KeyPart::Index(index) => item.get(index)?,
+        };
+    }
+    item.as_value()
 }
 
 #[derive(Clone, Default, Debug, clap::Args)]
@@ -640,6 +1841,10 @@ pub struct ConfigArgs {
     #[arg(long)]
     pub isolated: bool,
 
+    /// Layer the `[profile.<NAME>]` settings on top of the rest of the config
+    #[arg(long, value_name = "NAME")]
+    pub profile: Option<String>,
+
     /// Unstable options
     #[arg(short = 'Z', value_name = "FEATURE")]
     pub z: Vec<UnstableValues>,
@@ -766,9 +1971,9 @@ pub struct PublishArgs {
     #[arg(long, overrides_with("publish"))]
     no_publish: bool,
 
-    /// Cargo registry to upload to
+    /// Cargo registry to upload to, may be repeated
     #[arg(long, value_name = "NAME")]
-    registry: Option<String>,
+    registry: Vec<String>,
 
     #[arg(long, overrides_with("no_verify"), hide(true))]
     verify: bool,
@@ -793,7 +1998,11 @@ impl PublishArgs {
     pub fn to_config(&self) -> Config {
         Config {
             publish: resolve_bool_arg(self.publish, self.no_publish),
-            registry: self.registry.clone(),
+            registry: match self.registry.as_slice() {
+                [] => None,
+                [registry] => Some(Registries::Name(registry.clone())),
+                registries => Some(Registries::Names(registries.to_vec())),
+            },
             verify: resolve_bool_arg(self.verify, self.no_verify),
             enable_features: (!self.features.is_empty()).then(|| self.features.clone()),
             enable_all_features: self.all_features.then_some(true),
@@ -909,40 +2118,11 @@ pub fn resolve_custom_config(file_path: &Path) -> CargoResult<Option<Config>> {
 /// 2. $HOME/.config/cargo-release/release.toml
 /// 3. $(workspace)/release.toml
 /// 3. $(workspace)/Cargo.toml
+///
+/// Each source is preceded by the files it `extends`.
 pub fn resolve_workspace_config(workspace_root: &Path) -> CargoResult<Config> {
-    let mut config = Config::default();
-
-    // User-local configuration from home directory.
-    let home_dir = dirs_next::home_dir();
-    if let Some(mut home) = home_dir {
-        home.push(".release.toml");
-        if let Some(cfg) = get_config_from_file(&home)? {
-            config.update(&cfg);
-        }
-    };
-
-    let config_dir = dirs_next::config_dir();
-    if let Some(mut config_path) = config_dir {
-        config_path.push("cargo-release/release.toml");
-        if let Some(cfg) = get_config_from_file(&config_path)? {
-            config.update(&cfg);
-        }
-    };
-
-    // Workspace config
-    let default_config = workspace_root.join("release.toml");
-    let current_dir_config = get_config_from_file(&default_config)?;
-    if let Some(cfg) = current_dir_config {
-        config.update(&cfg);
-    };
-
-    let manifest_path = workspace_root.join("Cargo.toml");
-    let current_dir_config = get_ws_config_from_manifest(&manifest_path)?;
-    if let Some(cfg) = current_dir_config {
-        config.update(&cfg);
-    };
-
-    Ok(config)
+    let sources = config_sources(&ConfigArgs::default(), workspace_root, None);
+    Ok(merge_layers(&file_layers(None, None, sources)?))
 }
 
 /// Try to resolve configuration source.
@@ -957,23 +2137,19 @@ pub fn resolve_workspace_config(workspace_root: &Path) -> CargoResult<Config> {
 ///
 /// `$(crate)/Cargo.toml` is a way to differentiate configuration for the root crate and the
 /// workspace.
+///
+/// Each source is preceded by the files it `extends`.
 pub fn resolve_config(workspace_root: &Path, manifest_path: &Path) -> CargoResult<Config> {
-    let mut config = resolve_workspace_config(workspace_root)?;
-
-    // Crate config
-    let crate_root = manifest_path.parent().unwrap_or_else(|| Path::new("."));
-    let default_config = crate_root.join("release.toml");
-    let current_dir_config = get_config_from_file(&default_config)?;
-    if let Some(cfg) = current_dir_config {
-        config.update(&cfg);
-    };
-
-    let current_dir_config = get_pkg_config_from_manifest(manifest_path)?;
-    if let Some(cfg) = current_dir_config {
-        config.update(&cfg);
-    };
+    let sources = config_sources(&ConfigArgs::default(), workspace_root, Some(manifest_path));
+    Ok(merge_layers(&file_layers(None, None, sources)?))
+}
 
-    Ok(config)
+fn merge_layers(layers: &[ConfigLayer]) -> Config {
+    let mut config = Config::default();
+    for layer in layers {
+        config.update(&layer.config);
+    }
+    config
 }
 
 pub fn resolve_overrides(workspace_root: &Path, manifest_path: &Path) -> CargoResult<Config> {
@@ -1067,4 +2243,111 @@ mod test {
             assert!(!release_config.sign_commit());
         }
     }
+
+    mod registries {
+        use super::*;
+
+        fn config(registry: &str) -> Config {
+            toml::from_str(&format!("registry = {registry}")).unwrap()
+        }
+
+        #[test]
+        fn default() {
+            assert_eq!(Config::new().registries(), vec![None]);
+        }
+
+        #[test]
+        fn name() {
+            let config = config(r#""internal""#);
+            assert_eq!(config.registries(), vec![Some("internal")]);
+            assert_eq!(config.registry(), Some("internal"));
+        }
+
+        #[test]
+        fn names() {
+            let config = config(r#"["crates-io", "internal", "internal"]"#);
+            assert_eq!(config.registries(), vec![None, Some("internal")]);
+            assert_eq!(config.registry(), None);
+        }
+
+        #[test]
+        fn from_publish_field() {
+            let config = Config {
+                registry: publish_registry(&["internal".to_owned()]),
+                ..Default::default()
+            };
+            assert_eq!(config.registries(), vec![Some("internal")]);
+        }
+
+        #[test]
+        fn from_publish_field_with_several() {
+            let config = Config {
+                registry: publish_registry(&["crates-io".to_owned(), "internal".to_owned()]),
+                ..Default::default()
+            };
+            assert_eq!(config.registries(), vec![None]);
+        }
+
+        #[test]
+        fn not_allowed_by_publish_field() {
+            let config = config(r#"["crates-io", "internal"]"#);
+            resolve_registries(&config, "sample", &["internal".to_owned()]).unwrap_err();
+        }
+    }
+
+    mod env_keys {
+        use super::*;
+
+        #[test]
+        fn nested() {
+            let keys = env_keys();
+            assert!(keys.contains(&vec!["push-remote"]));
+            assert!(keys.contains(&vec!["rate-limit", "new-packages"]));
+            assert!(!keys.contains(&vec!["rate-limit"]));
+            assert!(!keys.contains(&vec!["profile"]));
+            assert_eq!(
+                env_var(&["rate-limit", "new-packages"]),
+                "CARGO_RELEASE_RATE_LIMIT_NEW_PACKAGES"
+            );
+        }
+    }
+
+    mod parse_env_value {
+        use super::*;
+
+        #[test]
+        fn bool() {
+            let value = parse_env_value(&["push"], "false").unwrap();
+            assert_eq!(value, toml::Value::Boolean(false));
+        }
+
+        #[test]
+        fn string() {
+            let value = parse_env_value(&["registry"], "my-registry").unwrap();
+            assert_eq!(value, toml::Value::String("my-registry".to_owned()));
+        }
+
+        #[test]
+        fn list() {
+            let value = parse_env_value(&["allow-branch"], "main, release/*").unwrap();
+            assert_eq!(
+                value,
+                toml::Value::Array(vec![
+                    toml::Value::String("main".to_owned()),
+                    toml::Value::String("release/*".to_owned()),
+                ])
+            );
+        }
+
+        #[test]
+        fn nested() {
+            let value = parse_env_value(&["rate-limit", "new-packages"], "10").unwrap();
+            assert_eq!(value, toml::Value::Integer(10));
+        }
+
+        #[test]
+        fn invalid() {
+            parse_env_value(&["push"], "sometimes").unwrap_err();
+        }
+    }
 }
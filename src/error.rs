@@ -36,6 +36,19 @@ process_error_from!(tame_index::external::reqwest::Error);
 process_error_from!(cargo_metadata::Error);
 process_error_from!(toml::ser::Error);
 process_error_from!(toml_edit::ser::Error);
+process_error_from!(serde_json::Error);
+
+/// An error whose details were already shown to the user
+#[derive(Debug)]
+pub struct Reported;
+
+impl std::fmt::Display for Reported {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        "aborting due to previous error".fmt(f)
+    }
+}
+
+impl std::error::Error for Reported {}
 
 impl From<i32> for CliError {
     fn from(code: i32) -> Self {
@@ -58,7 +71,7 @@ pub fn report(result: Result<(), CliError>) -> i32 {
     match result {
         Ok(()) => 0,
         Err(err) => {
-            if let Some(error) = err.error {
+            if let Some(error) = err.error.filter(|e| !e.is::<Reported>()) {
                 // At this point, we might be exiting due to a broken pipe, just do our best and
                 // move on.
                 let _ = crate::ops::shell::error(error);
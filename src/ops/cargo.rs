@@ -6,7 +6,7 @@ use itertools::Itertools as _;
 
 use crate::config::{self, CertsSource};
 use crate::error::CargoResult;
-use crate::ops::cmd::call;
+use crate::ops::cmd::call_capturing_stderr;
 
 /// Expresses what features flags should be used
 #[derive(Clone, Debug)]
@@ -50,6 +50,66 @@ pub fn package_content(manifest_path: &Path) -> CargoResult<Vec<std::path::PathB
     }
 }
 
+/// Why `cargo publish` failed
+#[derive(Clone, Debug)]
+pub struct PublishError {
+    /// What `cargo publish` reported
+    pub stderr: String,
+}
+
+impl PublishError {
+    /// Whether trying again may succeed, like after a network error
+    ///
+    /// Failures that aren't recognized are assumed to be permanent.
+    pub fn is_transient(&self) -> bool {
+        const PERMANENT: &[&str] = &[
+            "already exists",
+            "already uploaded",
+            "missing or empty metadata",
+            "failed to verify",
+        ];
+        const TRANSIENT: &[&str] = &[
+            // Server errors, like `status 503 Service Unavailable`
+            "(status 5",
+            "(status 429",
+            // Server errors without a message
+            "200 ok response, got 5",
+            "200 ok response, got 429",
+            "too many requests",
+            "timed out",
+            "timeout",
+            "spurious network error",
+            "failed to connect",
+            "couldn't connect",
+            "could not resolve host",
+            "couldn't resolve host",
+            "connection reset",
+            "connection refused",
+            "connection closed",
+        ];
+        let stderr = self.stderr.to_lowercase();
+        !PERMANENT.iter().any(|p| stderr.contains(p))
+            && TRANSIENT.iter().any(|t| stderr.contains(t))
+    }
+
+    /// When the registry asked to try again, after hitting its rate limit
+    ///
+    /// This looks for crates.io's "Please try again after <date>" in the registry's response.
+    pub fn retry_after(&self) -> Option<time::OffsetDateTime> {
+        if !self.stderr.contains("429") {
+            return None;
+        }
+        let (_, date) = self.stderr.split_once("try again after ")?;
+        let date = date.get(..date.find(" GMT")?)?;
+        let format = time::macros::format_description!(
+            "[weekday repr:short], [day] [month repr:short] [year] [hour]:[minute]:[second]"
+        );
+        time::PrimitiveDateTime::parse(date, format)
+            .ok()
+            .map(|date| date.assume_utc())
+    }
+}
+
 pub fn publish(
     dry_run: bool,
     verify: bool,
@@ -58,9 +118,9 @@ pub fn publish(
     features: &[&Features],
     registry: Option<&str>,
     target: Option<&str>,
-) -> CargoResult<bool> {
+) -> CargoResult<Result<(), PublishError>> {
     if pkgids.is_empty() {
-        return Ok(true);
+        return Ok(Ok(()));
     }
 
     let cargo = cargo();
@@ -118,7 +178,18 @@ pub fn publish(
         command.push(&selective);
     }
 
-    call(command, false)
+    // Cargo can't tell we are showing its output on a terminal
+    if anstream::stderr().current_choice() != anstream::ColorChoice::Never {
+        command.push("--color");
+        command.push("always");
+    }
+
+    let (success, stderr) = call_capturing_stderr(command, false)?;
+    if success {
+        Ok(Ok(()))
+    } else {
+        Ok(Err(PublishError { stderr }))
+    }
 }
 
 pub fn is_published(
@@ -127,15 +198,17 @@ pub fn is_published(
     name: &str,
     version: &str,
     certs_source: CertsSource,
-) -> bool {
+) -> Result<bool, crate::error::CliError> {
+    // Unlike reading an entry, not being able to connect won't go away on its own
+    index.connect(registry, certs_source)?;
     match index.has_krate_version(registry, name, version, certs_source) {
-        Ok(has_krate_version) => has_krate_version.unwrap_or(false),
+        Ok(has_krate_version) => Ok(has_krate_version.unwrap_or(false)),
         Err(err) => {
             // For both http and git indices, this _might_ be an error that goes away in
             // a future call, but at least printing out something should give the user
             // an indication something is amiss
             log::warn!("failed to read metadata for {name}: {err:#}");
-            false
+            Ok(false)
         }
     }
 }
@@ -146,12 +219,13 @@ pub fn is_published_req(
     name: &str,
     version_req: &semver::VersionReq,
     certs_source: CertsSource,
-) -> bool {
+) -> Result<bool, crate::error::CliError> {
+    index.connect(registry, certs_source)?;
     match index.has_krate_version_req(registry, name, version_req, certs_source) {
-        Ok(has_krate_version) => has_krate_version.unwrap_or(false),
+        Ok(has_krate_version) => Ok(has_krate_version.unwrap_or(false)),
         Err(err) => {
             log::warn!("failed to read metadata for {name}: {err:#}");
-            false
+            Ok(false)
         }
     }
 }
@@ -557,6 +631,138 @@ mod test {
     use assert_fs::prelude::*;
     use predicates::prelude::*;
 
+    mod is_transient {
+        use super::*;
+
+        fn error(stderr: &str) -> PublishError {
+            PublishError {
+                stderr: stderr.to_owned(),
+            }
+        }
+
+        #[test]
+        fn server_error() {
+            assert!(
+                error(
+                    "error: failed to publish to registry at https://crates.io
+
+Caused by:
+  the remote server responded with an error (status 503 Service Unavailable): Service Unavailable
+"
+                )
+                .is_transient()
+            );
+        }
+
+        #[test]
+        fn server_error_without_message() {
+            assert!(
+                error(
+                    "error: failed to publish sample v0.1.0 to registry at http://127.0.0.1:46447/
+
+Caused by:
+  failed to get a 200 OK response, got 500
+  headers:
+  \tHTTP/1.1 500
+"
+                )
+                .is_transient()
+            );
+        }
+
+        #[test]
+        fn rate_limited() {
+            assert!(error("error: failed to publish to registry at https://crates.io
+
+Caused by:
+  the remote server responded with an error (status 429 Too Many Requests): You have published too many new crates in a short period of time.
+").is_transient());
+        }
+
+        #[test]
+        fn network() {
+            assert!(
+                error(
+                    "error: failed to publish to registry at https://crates.io
+
+Caused by:
+  [28] Timeout was reached (Connection timed out after 30000 milliseconds)
+"
+                )
+                .is_transient()
+            );
+        }
+
+        #[test]
+        fn already_exists() {
+            assert!(
+                !error(
+                    "error: failed to publish to registry at https://crates.io
+
+Caused by:
+  the remote server responded with an error: crate version `0.1.0` is already uploaded
+"
+                )
+                .is_transient()
+            );
+        }
+
+        #[test]
+        fn missing_metadata() {
+            assert!(!error("error: failed to publish to registry at https://crates.io
+
+Caused by:
+  the remote server responded with an error (status 400 Bad Request): missing or empty metadata fields: description, license. Please see https://doc.rust-lang.org/cargo/reference/manifest.html for more information on configuring these fields
+").is_transient());
+        }
+
+        #[test]
+        fn verification() {
+            assert!(
+                !error(
+                    "error: failed to verify package tarball
+
+Caused by:
+  failed to connect the dots
+"
+                )
+                .is_transient()
+            );
+        }
+
+        #[test]
+        fn unknown() {
+            assert!(!error("error: something went wrong\n").is_transient());
+        }
+    }
+
+    mod retry_after {
+        use super::*;
+
+        #[test]
+        fn crates_io() {
+            let error = PublishError {
+                stderr: "error: failed to publish to registry at https://crates.io
+
+Caused by:
+  the remote server responded with an error (status 429 Too Many Requests): You have published too many new crates in a short period of time. Please try again after Tue, 29 Oct 2024 12:34:56 GMT or email help@crates.io to have your limit increased.
+".to_owned(),
+            };
+            assert_eq!(
+                error.retry_after(),
+                Some(time::macros::datetime!(2024-10-29 12:34:56 UTC))
+            );
+        }
+
+        #[test]
+        fn other_error() {
+            let error = PublishError {
+                stderr: "error: crate version `0.1.0` is already uploaded\n".to_owned(),
+            };
+            assert_eq!(error.retry_after(), None);
+        }
+    }
+
     mod set_package_version {
         use super::*;
 
@@ -1,5 +1,6 @@
 use std::collections::BTreeMap;
 use std::ffi::OsStr;
+use std::io::Write as _;
 use std::path::Path;
 use std::process::Command;
 
@@ -9,6 +10,8 @@ fn do_call(
     command: impl IntoIterator<Item = impl Into<String>>,
     path: Option<&Path>,
     envs: Option<BTreeMap<&OsStr, &OsStr>>,
+    input: Option<&[u8]>,
+    stderr: Option<&mut String>,
     dry_run: bool,
 ) -> CargoResult<bool> {
     let command: Vec<_> = command.into_iter().map(|s| s.into()).collect();
@@ -43,9 +46,48 @@ fn do_call(
         None => String::new(),
     };
 
+    if input.is_some() {
+        cmd.stdin(std::process::Stdio::piped());
+    }
+    if stderr.is_some() {
+        cmd.stderr(std::process::Stdio::piped());
+    }
+
     let mut child = cmd
         .spawn()
         .map_err(|e| anyhow::format_err!("failed to launch `{cmd_name}`{ctx_dir}: {e}"))?;
+    if let Some(input) = input {
+        let mut stdin = child.stdin.take().expect("stdin is piped");
+        // The command may exit without reading its input
+        if let Err(err) = stdin.write_all(input)
+            && err.kind() != std::io::ErrorKind::BrokenPipe
+        {
+            return Err(anyhow::format_err!(
+                "failed to write to `{cmd_name}`{ctx_dir}: {err}"
+            ));
+        }
+    }
+    if let Some(stderr) = stderr {
+        // Still show the output as it is written
+        let mut child_stderr = child.stderr.take().expect("stderr is piped");
+        let mut captured = Vec::new();
+        let mut buffer = [0; 4096];
+        loop {
+            let len = match std::io::Read::read(&mut child_stderr, &mut buffer) {
+                Ok(0) => break,
+                Ok(len) => len,
+                Err(err) if err.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(err) => {
+                    return Err(anyhow::format_err!(
+                        "failed to read output of `{cmd_name}`{ctx_dir}: {err}"
+                    ));
+                }
+            };
+            let _ = anstream::stderr().write_all(&buffer[..len]);
+            captured.extend_from_slice(&buffer[..len]);
+        }
+        *stderr = anstream::adapter::strip_str(&String::from_utf8_lossy(&captured)).to_string();
+    }
     let result = child
         .wait()
         .map_err(|e| anyhow::format_err!("failed to launch `{cmd_name}`{ctx_dir}: {e}"))?;
@@ -57,7 +99,19 @@ pub fn call(
     command: impl IntoIterator<Item = impl Into<String>>,
     dry_run: bool,
 ) -> CargoResult<bool> {
-    do_call(command, None, None, dry_run)
+    do_call(command, None, None, None, None, dry_run)
+}
+
+/// Like [`call`], also returning what the command wrote to stderr, without colors
+///
+/// The output is still shown as it is written.
+pub fn call_capturing_stderr(
+    command: impl IntoIterator<Item = impl Into<String>>,
+    dry_run: bool,
+) -> CargoResult<(bool, String)> {
+    let mut stderr = String::new();
+    let success = do_call(command, None, None, None, Some(&mut stderr), dry_run)?;
+    Ok((success, stderr))
 }
 
 pub fn call_on_path(
@@ -65,7 +119,7 @@ pub fn call_on_path(
     path: &Path,
     dry_run: bool,
 ) -> CargoResult<bool> {
-    do_call(command, Some(path), None, dry_run)
+    do_call(command, Some(path), None, None, None, dry_run)
 }
 
 pub fn call_with_env(
@@ -74,5 +128,15 @@ pub fn call_with_env(
     path: &Path,
     dry_run: bool,
 ) -> CargoResult<bool> {
-    do_call(command, Some(path), Some(envs), dry_run)
+    do_call(command, Some(path), Some(envs), None, None, dry_run)
+}
+
+pub fn call_with_input(
+    command: impl IntoIterator<Item = impl Into<String>>,
+    envs: BTreeMap<&OsStr, &OsStr>,
+    input: &[u8],
+    path: &Path,
+    dry_run: bool,
+) -> CargoResult<bool> {
+    do_call(command, Some(path), Some(envs), Some(input), None, dry_run)
 }
@@ -80,6 +80,16 @@ pub fn current_branch(dir: &Path) -> CargoResult<String> {
     Ok(name.to_owned())
 }
 
+/// The checked out branch, or `None` on a detached `HEAD`
+pub fn branch(dir: &Path) -> CargoResult<Option<String>> {
+    let repo = git2::Repository::discover(dir)?;
+
+    if repo.head_detached()? {
+        return Ok(None);
+    }
+    current_branch(dir).map(Some)
+}
+
 pub fn is_dirty(dir: &Path) -> CargoResult<Option<Vec<String>>> {
     let repo = git2::Repository::discover(dir)?;
 
@@ -158,6 +168,11 @@ pub fn commit_all(dir: &Path, msg: &str, sign: bool, dry_run: bool) -> CargoResu
     }
 }
 
+pub fn add(dir: &Path, path: &Path, dry_run: bool) -> CargoResult<bool> {
+    let path = path.to_string_lossy();
+    call_on_path(vec!["git", "add", "--", &path], dir, dry_run)
+}
+
 pub fn tag(dir: &Path, name: &str, msg: &str, sign: bool, dry_run: bool) -> CargoResult<bool> {
     let mut cmd = vec!["git", "tag", name];
     if !msg.is_empty() {
@@ -176,6 +191,24 @@ pub fn tag_exists(dir: &Path, name: &str) -> CargoResult<bool> {
     Ok(!names.is_empty())
 }
 
+pub fn head_id(dir: &Path) -> CargoResult<String> {
+    let repo = git2::Repository::discover(dir)?;
+
+    let head = repo.head()?.peel_to_commit()?;
+    Ok(head.id().to_string())
+}
+
+/// Content hash of a file, as git would store it
+pub fn blob_id(path: &Path) -> CargoResult<String> {
+    let content = std::fs::read(path)?;
+    let id = git2::Oid::hash_object(git2::ObjectType::Blob, &content)?;
+    Ok(id.to_string())
+}
+
+pub fn delete_tag(dir: &Path, name: &str, dry_run: bool) -> CargoResult<bool> {
+    call_on_path(vec!["git", "tag", "--delete", name], dir, dry_run)
+}
+
 pub fn find_last_tag(dir: &Path, glob: &globset::GlobMatcher) -> Option<String> {
     let repo = git2::Repository::discover(dir).ok()?;
     let mut tags: std::collections::HashMap<git2::Oid, String> = Default::default();
@@ -207,6 +240,70 @@ pub fn find_last_tag(dir: &Path, glob: &globset::GlobMatcher) -> Option<String>
     Some(name)
 }
 
+/// Files that differ between `rev` and the worktree, flagged `true` when `rev` doesn't have them
+pub fn changed_since(dir: &Path, rev: &str) -> CargoResult<Vec<(PathBuf, bool)>> {
+    let root = top_level(dir)?;
+
+    let output = Command::new("git")
+        .arg("diff")
+        .arg("--name-status")
+        .arg("--no-renames")
+        .arg("-z")
+        .arg(rev)
+        .current_dir(&root)
+        .output()?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "failed to diff against {rev}: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    let mut fields = output.stdout.split_str("\0").filter(|f| !f.is_empty());
+    let mut changed = Vec::new();
+    while let (Some(status), Some(path)) = (fields.next(), fields.next()) {
+        changed.push((root.join(path.to_path_lossy()), status == b"A"));
+    }
+    Ok(changed)
+}
+
+/// Move HEAD and the index to `rev`, leaving the worktree alone
+pub fn reset_mixed(dir: &Path, rev: &str, dry_run: bool) -> CargoResult<bool> {
+    call_on_path(
+        vec!["git", "reset", "--mixed", "--quiet", rev],
+        dir,
+        dry_run,
+    )
+}
+
+pub fn checkout_paths(
+    dir: &Path,
+    rev: &str,
+    paths: &[PathBuf],
+    dry_run: bool,
+) -> CargoResult<bool> {
+    let mut command = vec![
+        "git".to_owned(),
+        "checkout".to_owned(),
+        rev.to_owned(),
+        "--".to_owned(),
+    ];
+    command.extend(paths.iter().map(|p| p.display().to_string()));
+    call_on_path(command, dir, dry_run)
+}
+
+/// Number of commits HEAD is ahead of `rev`, if `rev` is an ancestor of HEAD
+pub fn commits_since(dir: &Path, rev: &str) -> CargoResult<Option<usize>> {
+    let repo = git2::Repository::discover(dir)?;
+
+    let head = repo.head()?.peel_to_commit()?.id();
+    let base = repo.revparse_single(rev)?.peel_to_commit()?.id();
+    let (ahead, behind) = repo.graph_ahead_behind(head, base)?;
+    if behind != 0 {
+        return Ok(None);
+    }
+    Ok(Some(ahead))
+}
+
 pub fn push<'s>(
     dir: &Path,
     remote: &str,
@@ -245,6 +342,12 @@ pub fn top_level(dir: &Path) -> CargoResult<PathBuf> {
         .ok_or_else(|| anyhow::format_err!("bare repos are unsupported"))
 }
 
+pub fn git_dir(dir: &Path) -> CargoResult<PathBuf> {
+    let repo = git2::Repository::discover(dir)?;
+
+    Ok(repo.path().to_owned())
+}
+
 pub fn git_version() -> CargoResult<()> {
     Command::new("git")
         .arg("--version")
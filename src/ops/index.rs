@@ -1,18 +1,23 @@
+use anyhow::Context as _;
+
 use crate::config::CertsSource;
 use tame_index::krate::IndexKrate;
 use tame_index::utils::flock::FileLock;
 
+/// The name Cargo gives crates.io in `--registry`
+pub const CRATES_IO: &str = "crates-io";
+
 #[derive(Default)]
 pub struct CratesIoIndex {
-    index: Option<RemoteIndex>,
-    cache: std::collections::HashMap<String, Option<IndexKrate>>,
+    indexes: std::collections::HashMap<String, RemoteIndex>,
+    cache: std::collections::HashMap<(String, String), Option<IndexKrate>>,
 }
 
 impl CratesIoIndex {
     #[inline]
     pub fn new() -> Self {
         Self {
-            index: None,
+            indexes: std::collections::HashMap::new(),
             cache: std::collections::HashMap::new(),
         }
     }
@@ -62,13 +67,25 @@ impl CratesIoIndex {
         }))
     }
 
+    /// The highest published version, including pre-releases and yanked versions
     #[inline]
-    pub fn update_krate(&mut self, registry: Option<&str>, name: &str) {
-        if registry.is_some() {
-            return;
-        }
+    pub fn highest_krate_version(
+        &mut self,
+        registry: Option<&str>,
+        name: &str,
+        certs_source: CertsSource,
+    ) -> Result<Option<String>, crate::error::CliError> {
+        let krate = self.krate(registry, name, certs_source)?;
+        Ok(krate.map(|ik| ik.highest_version().version.to_string()))
+    }
 
-        self.cache.remove(name);
+    /// Forget the cached entry, so the next lookup asks the registry again
+    ///
+    /// Sparse indexes revalidate with the entry's etag, so an unchanged entry is cheap.
+    #[inline]
+    pub fn update_krate(&mut self, registry: Option<&str>, name: &str) {
+        let registry = registry.unwrap_or(CRATES_IO);
+        self.cache.remove(&(registry.to_owned(), name.to_owned()));
     }
 
     pub(crate) fn krate(
@@ -77,41 +94,165 @@ impl CratesIoIndex {
         name: &str,
         certs_source: CertsSource,
     ) -> Result<Option<IndexKrate>, crate::error::CliError> {
-        if let Some(registry) = registry {
-            log::trace!("Cannot connect to registry `{registry}`");
-            return Ok(None);
-        }
-
-        if let Some(entry) = self.cache.get(name) {
+        let registry = registry.unwrap_or(CRATES_IO);
+        let key = (registry.to_owned(), name.to_owned());
+        if let Some(entry) = self.cache.get(&key) {
             log::trace!("Reusing index for {name}");
             return Ok(entry.clone());
         }
 
-        if self.index.is_none() {
-            log::trace!("Connecting to index");
-            self.index = Some(RemoteIndex::open(certs_source)?);
-        }
-        let index = self.index.as_mut().unwrap();
+        let index = self.index(Some(registry), certs_source)?;
         log::trace!("Downloading index for {name}");
         let entry = index.krate(name)?;
-        self.cache.insert(name.to_owned(), entry.clone());
+        self.cache.insert(key, entry.clone());
         Ok(entry)
     }
+
+    /// Connect to the registry's index, failing when it can't be queried
+    pub fn connect(
+        &mut self,
+        registry: Option<&str>,
+        certs_source: CertsSource,
+    ) -> Result<(), crate::error::CliError> {
+        self.index(registry, certs_source).map(|_| ())
+    }
+
+    fn index(
+        &mut self,
+        registry: Option<&str>,
+        certs_source: CertsSource,
+    ) -> Result<&mut RemoteIndex, crate::error::CliError> {
+        let registry = registry.unwrap_or(CRATES_IO);
+        if !self.indexes.contains_key(registry) {
+            log::trace!("Connecting to index for `{registry}`");
+            let index = if registry == CRATES_IO {
+                RemoteIndex::open(certs_source)?
+            } else {
+                RemoteIndex::open_registry(registry, certs_source)?
+            };
+            self.indexes.insert(registry.to_owned(), index);
+        }
+        Ok(self.indexes.get_mut(registry).unwrap())
+    }
+}
+
+/// A registry's index, however it is stored
+pub enum RemoteIndex {
+    Sparse(SparseIndex),
+    File(FileIndex),
+    Local(LocalIndex),
+    Git(GitIndex),
+}
+
+impl RemoteIndex {
+    /// Open the crates.io index, respecting [source replacement](https://doc.rust-lang.org/cargo/reference/source-replacement.html)
+    #[inline]
+    pub fn open(certs_source: CertsSource) -> Result<Self, crate::error::CliError> {
+        let url = match tame_index::IndexUrl::crates_io(None, None, None)? {
+            // The sparse index has the same content without needing a clone
+            tame_index::IndexUrl::CratesIoGit => tame_index::IndexUrl::CratesIoSparse,
+            url => url,
+        };
+        Self::with_url(url, None, certs_source)
+    }
+
+    /// Open the index of an alternative registry, configured in `[registries.<name>]`
+    pub fn open_registry(
+        registry: &str,
+        certs_source: CertsSource,
+    ) -> Result<Self, crate::error::CliError> {
+        let url = tame_index::IndexUrl::for_registry_name(None, None, registry)?;
+        let token = if url.is_sparse() {
+            registry_token(registry)?
+        } else {
+            None
+        };
+        Self::with_url(url, token, certs_source)
+    }
+
+    fn with_url(
+        url: tame_index::IndexUrl<'_>,
+        token: Option<String>,
+        certs_source: CertsSource,
+    ) -> Result<Self, crate::error::CliError> {
+        if let Some(path) = url.as_str().strip_prefix("sparse+").and_then(file_path) {
+            return Ok(Self::File(FileIndex { root: path }));
+        }
+        if url.is_sparse() {
+            return SparseIndex::open(url, token, certs_source).map(Self::Sparse);
+        }
+        match url {
+            tame_index::IndexUrl::Local(path) => {
+                Ok(Self::Local(LocalIndex::open(path.into_owned())?))
+            }
+            tame_index::IndexUrl::NonCratesIo(url) if url.starts_with("file://") => {
+                Ok(Self::Git(GitIndex::open(&url)?))
+            }
+            url => {
+                let _ = crate::ops::shell::error(format!(
+                    "cannot read the index at `{}`, only sparse indexes, local registries and `file://` git indexes are supported",
+                    url.as_str()
+                ));
+                Err(101.into())
+            }
+        }
+    }
+
+    pub(crate) fn krate(
+        &mut self,
+        name: &str,
+    ) -> Result<Option<IndexKrate>, crate::error::CliError> {
+        match self {
+            Self::Sparse(index) => index.krate(name),
+            Self::File(index) => index.krate(name).map_err(Into::into),
+            Self::Local(index) => index.krate(name),
+            Self::Git(index) => Ok(index.krate(name)?),
+        }
+    }
+}
+
+/// A sparse index on the local filesystem, e.g. `sparse+file:///path/to/index`
+pub struct FileIndex {
+    root: std::path::PathBuf,
 }
 
-pub struct RemoteIndex {
+impl FileIndex {
+    pub(crate) fn krate(&self, name: &str) -> crate::error::CargoResult<Option<IndexKrate>> {
+        let krate_name: tame_index::KrateName<'_> = name.try_into()?;
+        let path = self.root.join(krate_name.relative_path(None));
+        match std::fs::read(&path) {
+            Ok(content) => Ok(Some(IndexKrate::from_slice(&content)?)),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err).with_context(|| format!("Failed to read `{}`", path.display())),
+        }
+    }
+}
+
+fn file_path(url: &str) -> Option<std::path::PathBuf> {
+    tame_index::external::reqwest::Url::parse(url)
+        .ok()
+        .filter(|url| url.scheme() == "file")
+        .and_then(|url| url.to_file_path().ok())
+}
+
+/// A sparse index served over HTTP
+pub struct SparseIndex {
     index: tame_index::SparseIndex,
     client: tame_index::external::reqwest::blocking::Client,
+    token: Option<String>,
     lock: FileLock,
     etags: Vec<(String, String)>,
+    /// The last entry downloaded for each crate, reused when its etag is still current
+    entries: std::collections::HashMap<String, IndexKrate>,
 }
 
-impl RemoteIndex {
-    #[inline]
-    pub fn open(certs_source: CertsSource) -> Result<Self, crate::error::CliError> {
-        let index = tame_index::SparseIndex::new(tame_index::IndexLocation::new(
-            tame_index::IndexUrl::CratesIoSparse,
-        ))?;
+impl SparseIndex {
+    fn open(
+        url: tame_index::IndexUrl<'_>,
+        token: Option<String>,
+        certs_source: CertsSource,
+    ) -> Result<Self, crate::error::CliError> {
+        let index = tame_index::SparseIndex::new(tame_index::IndexLocation::new(url))?;
 
         let client = {
             let builder = tame_index::external::reqwest::blocking::ClientBuilder::new();
@@ -129,8 +270,10 @@ impl RemoteIndex {
         Ok(Self {
             index,
             client,
+            token,
             lock,
             etags: Vec::new(),
+            entries: std::collections::HashMap::new(),
         })
     }
 
@@ -138,10 +281,13 @@ impl RemoteIndex {
         &mut self,
         name: &str,
     ) -> Result<Option<IndexKrate>, crate::error::CliError> {
+        // Without the entry, a `304 Not Modified` would fall back to Cargo's cache, which may be
+        // stale
         let etag = self
             .etags
             .iter()
             .find_map(|(krate, etag)| (krate == name).then_some(etag.as_str()))
+            .filter(|_| self.entries.contains_key(name))
             .unwrap_or("");
 
         let krate_name = name.try_into()?;
@@ -158,10 +304,23 @@ impl RemoteIndex {
             },
             _,
         ) = req.into_parts();
-        let mut req = self.client.request(method, uri.to_string());
-        req = req.version(version);
-        req = req.headers(headers);
-        let res = self.client.execute(req.build()?)?;
+        let send = |token: Option<&str>| {
+            let mut req = self.client.request(method.clone(), uri.to_string());
+            req = req.version(version);
+            req = req.headers(headers.clone());
+            if let Some(token) = token {
+                req = req.header(tame_index::external::reqwest::header::AUTHORIZATION, token);
+            }
+            self.client.execute(req.build()?)
+        };
+        let mut res = send(None)?;
+        // Like Cargo, only share the token with registries that ask for it
+        if res.status() == tame_index::external::reqwest::StatusCode::UNAUTHORIZED
+            && let Some(token) = self.token.as_deref()
+        {
+            log::trace!("Authenticating to download index for {name}");
+            res = send(Some(token))?;
+        }
 
         // Grab the etag if it exists for future requests
         if let Some(etag) = res
@@ -176,6 +335,13 @@ impl RemoteIndex {
             }
         }
 
+        if res.status() == tame_index::external::reqwest::StatusCode::NOT_MODIFIED
+            && let Some(entry) = self.entries.get(name)
+        {
+            log::trace!("Index for {name} is unchanged");
+            return Ok(Some(entry.clone()));
+        }
+
         let mut builder = tame_index::external::http::Response::builder()
             .status(res.status())
             .version(res.version());
@@ -190,8 +356,174 @@ impl RemoteIndex {
             .body(body.to_vec())
             .map_err(|e| tame_index::Error::from(tame_index::error::HttpError::from(e)))?;
 
-        self.index
-            .parse_remote_response(krate_name, response, false, &self.lock)
+        let entry = self
+            .index
+            .parse_remote_response(krate_name, response, false, &self.lock)?;
+        if let Some(entry) = entry.as_ref() {
+            self.entries.insert(name.to_owned(), entry.clone());
+        } else {
+            self.entries.remove(name);
+        }
+        Ok(entry)
+    }
+}
+
+/// Find the name of the registry whose index is at `index_url`, like Cargo records for dependencies
+///
+/// Registries are looked up in `[registries.<name>]` of the Cargo config, starting from the
+/// current directory, with `CARGO_REGISTRIES_<NAME>_INDEX` taking precedence.
+pub fn registry_name(index_url: &str) -> crate::error::CargoResult<Option<String>> {
+    let index_url = tame_index::utils::canonicalize_url(index_url)?;
+
+    let mut seen = std::collections::HashSet::new();
+    for config in cargo_configs()? {
+        let Some(registries) = config.get("registries").and_then(|r| r.as_table()) else {
+            continue;
+        };
+        for (name, registry) in registries {
+            // Closer configs take precedence
+            if !seen.insert(name.clone()) {
+                continue;
+            }
+            let env = format!(
+                "CARGO_REGISTRIES_{}_INDEX",
+                name.to_uppercase().replace('-', "_")
+            );
+            let url = match std::env::var(&env) {
+                Ok(url) => url,
+                Err(_) => {
+                    let Some(url) = registry.get("index").and_then(|i| i.as_str()) else {
+                        continue;
+                    };
+                    url.to_owned()
+                }
+            };
+            if tame_index::utils::canonicalize_url(&url)? == index_url {
+                return Ok(Some(name.clone()));
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+/// Cargo config files, closest to the current directory first
+fn cargo_configs() -> crate::error::CargoResult<Vec<toml::Table>> {
+    let mut paths = Vec::new();
+    let mut dir = Some(std::env::current_dir()?);
+    while let Some(current) = dir {
+        paths.push(current.join(".cargo/config.toml"));
+        paths.push(current.join(".cargo/config"));
+        dir = current.parent().map(ToOwned::to_owned);
+    }
+    let cargo_home = tame_index::utils::cargo_home()?;
+    paths.push(cargo_home.join("config.toml").into_std_path_buf());
+    paths.push(cargo_home.join("config").into_std_path_buf());
+    read_tables(paths)
+}
+
+fn read_tables(
+    paths: impl IntoIterator<Item = std::path::PathBuf>,
+) -> crate::error::CargoResult<Vec<toml::Table>> {
+    let mut tables = Vec::new();
+    for path in paths {
+        if !path.exists() {
+            continue;
+        }
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read `{}`", path.display()))?;
+        let table = toml::from_str(&content)
+            .with_context(|| format!("Failed to parse `{}`", path.display()))?;
+        tables.push(table);
+    }
+    Ok(tables)
+}
+
+/// A [local registry](https://doc.rust-lang.org/cargo/reference/source-replacement.html#local-registry-sources)
+pub struct LocalIndex {
+    registry: tame_index::index::LocalRegistry,
+    lock: FileLock,
+}
+
+impl LocalIndex {
+    pub fn open(path: tame_index::PathBuf) -> Result<Self, crate::error::CliError> {
+        // Validating checksums reads every `.crate` file, more than we need
+        let registry = tame_index::index::LocalRegistry::open(path, false)?;
+        Ok(Self {
+            registry,
+            lock: FileLock::unlocked(),
+        })
+    }
+
+    pub(crate) fn krate(&self, name: &str) -> Result<Option<IndexKrate>, crate::error::CliError> {
+        let krate_name = name.try_into()?;
+        self.registry
+            .cached_krate(krate_name, &self.lock)
             .map_err(Into::into)
     }
 }
+
+/// A git index on the local filesystem, like a mirror of crates.io's index
+///
+/// `tame_index`'s `GitIndex` only reads entries Cargo already cached from a fetch and its
+/// `RemoteGitIndex` needs `gix`, so this reads the repository's `HEAD` with `git2` instead.
+pub struct GitIndex {
+    repo: git2::Repository,
+}
+
+impl GitIndex {
+    pub fn open(url: &str) -> crate::error::CargoResult<Self> {
+        let path =
+            file_path(url).ok_or_else(|| anyhow::format_err!("`{url}` is not a local path"))?;
+        let repo = git2::Repository::open(&path)
+            .with_context(|| format!("Failed to open index at `{}`", path.display()))?;
+        Ok(Self { repo })
+    }
+
+    pub(crate) fn krate(&self, name: &str) -> crate::error::CargoResult<Option<IndexKrate>> {
+        let krate_name: tame_index::KrateName<'_> = name.try_into()?;
+        let tree = self.repo.head()?.peel_to_tree()?;
+        let entry = match tree.get_path(std::path::Path::new(&krate_name.relative_path(Some('/'))))
+        {
+            Ok(entry) => entry,
+            Err(err) if err.code() == git2::ErrorCode::NotFound => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+        let blob = entry.to_object(&self.repo)?.peel_to_blob()?;
+        Ok(Some(IndexKrate::from_slice(blob.content())?))
+    }
+}
+
+/// Look up the token for `registry` the way Cargo does
+///
+/// Credential providers are not supported.
+fn registry_token(registry: &str) -> crate::error::CargoResult<Option<String>> {
+    let env = format!(
+        "CARGO_REGISTRIES_{}_TOKEN",
+        registry.to_uppercase().replace('-', "_")
+    );
+    if let Ok(token) = std::env::var(&env) {
+        return Ok(Some(token));
+    }
+
+    let cargo_home = tame_index::utils::cargo_home()?.into_std_path_buf();
+    let credentials = [
+        cargo_home.join("credentials.toml"),
+        cargo_home.join("credentials"),
+    ];
+    for config in read_tables(credentials)?
+        .into_iter()
+        .chain(cargo_configs()?)
+    {
+        let token = config
+            .get("registries")
+            .and_then(|r| r.get(registry))
+            .and_then(|r| r.get("token"))
+            .and_then(|t| t.as_str());
+        if let Some(token) = token {
+            return Ok(Some(token.to_owned()));
+        }
+    }
+
+    Ok(None)
+}
@@ -4,6 +4,7 @@ pub mod git;
 pub mod index;
 pub mod replace;
 pub mod shell;
+pub mod template;
 pub mod version;
 
 pub(crate) mod diff;
@@ -1,8 +1,9 @@
 use std::collections::BTreeMap;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
-use crate::config::Replace;
+use crate::config::{Replace, ReplaceCounts};
 use crate::error::CargoResult;
+use crate::ops::template::{Node, OPTIONAL_VARIABLES, VERSION_VARIABLES};
 
 pub static NOW: std::sync::LazyLock<String> = std::sync::LazyLock::new(|| {
     time::OffsetDateTime::now_utc()
@@ -19,6 +20,13 @@ pub struct Template<'a> {
     pub crate_name: Option<&'a str>,
     pub repository: Option<&'a str>,
     pub date: Option<&'a str>,
+    pub next_major: Option<&'a str>,
+    pub next_minor: Option<&'a str>,
+    pub next_patch: Option<&'a str>,
+    pub branch: Option<&'a str>,
+    pub head_sha: Option<&'a str>,
+    pub prev_tag: Option<&'a str>,
+    pub registry: Option<&'a str>,
 
     pub prefix: Option<&'a str>,
     pub tag_name: Option<&'a str>,
@@ -26,39 +34,503 @@ pub struct Template<'a> {
 
 impl Template<'_> {
     pub fn render(&self, input: &str) -> String {
-        const PREV_VERSION: &str = "{{prev_version}}";
-        const PREV_METADATA: &str = "{{prev_metadata}}";
-        const VERSION: &str = "{{version}}";
-        const METADATA: &str = "{{metadata}}";
-        const CRATE_NAME: &str = "{{crate_name}}";
-        const REPOSITORY: &str = "{{repository}}";
-        const DATE: &str = "{{date}}";
+        match crate::ops::template::parse(input) {
+            Ok(nodes) => {
+                let mut output = String::with_capacity(input.len());
+                self.render_nodes(input, &nodes, &mut output);
+                output
+            }
+            Err(err) => {
+                log::warn!("Unrendered template {input:?}: {err}");
+                input.to_owned()
+            }
+        }
+    }
+
+    fn render_nodes(&self, input: &str, nodes: &[Node<'_>], output: &mut String) {
+        for node in nodes {
+            match node {
+                Node::Text(text) => output.push_str(text),
+                Node::Var(var) => {
+                    let value = self.value(var.name).and_then(|value| {
+                        var.filters
+                            .iter()
+                            .try_fold(value.into_owned(), |value, filter| filter.apply(&value))
+                    });
+                    if let Some(value) = value {
+                        output.push_str(&value);
+                    } else if OPTIONAL_VARIABLES.contains(&var.name)
+                        && self.value(var.name).is_none()
+                    {
+                        // Unset, like `prev_tag` on a first release, so test it with `#if`
+                    } else {
+                        log::warn!("Unrendered {} present in template {input:?}", var.raw);
+                        output.push_str(var.raw);
+                    }
+                }
+                Node::If {
+                    cond,
+                    then,
+                    otherwise,
+                } => {
+                    let value = self.value(cond.name);
+                    let is_set = value.is_some_and(|v| !v.is_empty() && v != "false");
+                    let branch = if is_set { then } else { otherwise };
+                    self.render_nodes(input, branch, output);
+                }
+            }
+        }
+    }
+
+    /// Look up a variable, like `version` or `version.major`
+    fn value(&self, name: &str) -> Option<std::borrow::Cow<'_, str>> {
+        let (base, component) = match name.split_once('.') {
+            Some((base, component)) => (base, Some(component)),
+            None => (name, None),
+        };
+        let value = match base {
+            "prev_version" => self.prev_version,
+            "prev_metadata" => self.prev_metadata,
+            "version" => self.version,
+            "metadata" => self.metadata,
+            "crate_name" => self.crate_name,
+            "repository" => self.repository,
+            "date" => self.date,
+            "next_major" => self.next_major,
+            "next_minor" => self.next_minor,
+            "next_patch" => self.next_patch,
+            "branch" => self.branch,
+            "head_sha" => self.head_sha,
+            "prev_tag" => self.prev_tag,
+            "registry" => self.registry,
+            "prefix" => self.prefix,
+            "tag_name" => self.tag_name,
+            "prerelease" if component.is_none() => {
+                let version = semver::Version::parse(self.version?).ok()?;
+                let prerelease = if version.pre.is_empty() {
+                    "false"
+                } else {
+                    "true"
+                };
+                return Some(prerelease.into());
+            }
+            _ => None,
+        }?;
+        let Some(component) = component else {
+            return Some(value.into());
+        };
+        if !VERSION_VARIABLES.contains(&base) {
+            return None;
+        }
+        let Ok(version) = semver::Version::parse(value) else {
+            // Not a concrete version, like the `*` used for finding tags
+            return Some(value.into());
+        };
+        let component = match component {
+            "major" => version.major.to_string(),
+            "minor" => version.minor.to_string(),
+            "patch" => version.patch.to_string(),
+            "pre" => version.pre.to_string(),
+            "build" => version.build.to_string(),
+            _ => return None,
+        };
+        Some(component.into())
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum FileFormat {
+    Toml,
+    Json,
+    Yaml,
+}
+
+impl FileFormat {
+    fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "toml" => Some(Self::Toml),
+            "json" => Some(Self::Json),
+            "yaml" | "yml" => Some(Self::Yaml),
+            _ => None,
+        }
+    }
+}
+
+/// Set the value at the dotted `key` path, preserving the rest of the file's formatting
+fn set_key(data: &str, path: &Path, key: &str, value: &str) -> CargoResult<String> {
+    let Some(format) = FileFormat::from_path(path) else {
+        anyhow::bail!("`key` is only supported for TOML, JSON, and YAML files");
+    };
+    let segments = parse_key(key)?;
+    match format {
+        FileFormat::Toml => set_toml_key(data, &segments, value),
+        FileFormat::Json | FileFormat::Yaml => set_yaml_key(data, format, &segments, value),
+    }
+}
+
+/// Split a key path like `dependencies."my-crate".version` into its segments
+fn parse_key(key: &str) -> CargoResult<Vec<String>> {
+    let mut segments = Vec::new();
+    let mut chars = key.chars().peekable();
+    loop {
+        let mut segment = String::new();
+        if chars.peek() == Some(&'"') {
+            chars.next();
+            loop {
+                match chars.next() {
+                    Some('"') => break,
+                    Some(c) => segment.push(c),
+                    None => anyhow::bail!("unterminated quote in key"),
+                }
+            }
+            if !matches!(chars.peek(), None | Some('.')) {
+                anyhow::bail!("expected `.` after quoted segment in key");
+            }
+        } else {
+            while let Some(c) = chars.next_if(|c| *c != '.') {
+                segment.push(c);
+            }
+            if segment.is_empty() {
+                anyhow::bail!("empty segment in key");
+            }
+        }
+        segments.push(segment);
+        if chars.next().is_none() {
+            break;
+        }
+    }
+    Ok(segments)
+}
+
+fn set_toml_key(data: &str, segments: &[String], value: &str) -> CargoResult<String> {
+    let mut doc = data.parse::<toml_edit::DocumentMut>()?;
+    let old = toml_item_value(doc.as_item_mut(), segments)
+        .ok_or_else(|| anyhow::format_err!("no value found at key"))?;
+    let decor = old.decor().clone();
+    *old = toml_replacement(old, value)?;
+    *old.decor_mut() = decor;
+    Ok(doc.to_string())
+}
+
+/// Render `value` with the type and quoting of the value it replaces
+fn toml_replacement(old: &toml_edit::Value, value: &str) -> CargoResult<toml_edit::Value> {
+    let raw = match old {
+        toml_edit::Value::String(formatted) => {
+            let quote = formatted
+                .as_repr()
+                .and_then(|repr| repr.as_raw().as_str())
+                .unwrap_or("\"");
+            if quote.starts_with("'''") {
+                format!("'''{value}'''")
+            } else if quote.starts_with('\'') {
+                format!("'{value}'")
+            } else if quote.starts_with(r#"""""#) {
+                format!(r#""""{}""""#, escape_toml_basic(value, true))
+            } else {
+                format!(r#""{}""#, escape_toml_basic(value, false))
+            }
+        }
+        toml_edit::Value::Array(_) | toml_edit::Value::InlineTable(_) => {
+            anyhow::bail!("cannot replace {}, only single values", old.type_name())
+        }
+        _ => value.to_owned(),
+    };
+    raw.parse::<toml_edit::Value>()
+        .ok()
+        .filter(|new| new.type_name() == old.type_name())
+        .filter(|new| new.as_str().is_none_or(|new| new == value))
+        .ok_or_else(|| anyhow::format_err!("`{value}` cannot be written as a {}", old.type_name()))
+}
+
+fn escape_toml_basic(value: &str, multiline: bool) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str(r"\\"),
+            '"' => escaped.push_str(r#"\""#),
+            '\n' if multiline => escaped.push(c),
+            '\n' => escaped.push_str(r"\n"),
+            '\r' => escaped.push_str(r"\r"),
+            '\t' => escaped.push_str(r"\t"),
+            c if c.is_control() => escaped.push_str(&format!(r"\u{:04X}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn toml_item_value<'i>(
+    item: &'i mut toml_edit::Item,
+    segments: &[String],
+) -> Option<&'i mut toml_edit::Value> {
+    let Some((segment, rest)) = segments.split_first() else {
+        return item.as_value_mut();
+    };
+    match item {
+        toml_edit::Item::Table(table) => toml_item_value(table.get_mut(segment)?, rest),
+        toml_edit::Item::ArrayOfTables(array) => {
+            let table = array.get_mut(segment.parse().ok()?)?;
+            let (segment, rest) = rest.split_first()?;
+            toml_item_value(table.get_mut(segment)?, rest)
+        }
+        toml_edit::Item::Value(value) => toml_value(value, segments),
+        toml_edit::Item::None => None,
+    }
+}
+
+fn toml_value<'v>(
+    value: &'v mut toml_edit::Value,
+    segments: &[String],
+) -> Option<&'v mut toml_edit::Value> {
+    let Some((segment, rest)) = segments.split_first() else {
+        return Some(value);
+    };
+    match value {
+        toml_edit::Value::InlineTable(table) => toml_value(table.get_mut(segment)?, rest),
+        toml_edit::Value::Array(array) => toml_value(array.get_mut(segment.parse().ok()?)?, rest),
+        _ => None,
+    }
+}
+
+fn set_yaml_key(
+    data: &str,
+    format: FileFormat,
+    segments: &[String],
+    value: &str,
+) -> CargoResult<String> {
+    let events = saphyr_parser::Parser::new_from_str(data)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| anyhow::format_err!("{e}"))?;
+    let mut events = events.into_iter().peekable();
+    // Only the first document is considered
+    while events.next_if(|(e, _)| !is_node_start(e)).is_some() {}
+    let (style, span) = find_yaml_scalar(&mut events, segments)?
+        .ok_or_else(|| anyhow::format_err!("no value found at key"))?;
+
+    let rendered = match (format, style) {
+        (FileFormat::Json, _) | (_, saphyr_parser::ScalarStyle::DoubleQuoted) => {
+            serde_json::to_string(value)?
+        }
+        (_, saphyr_parser::ScalarStyle::SingleQuoted) => {
+            format!("'{}'", value.replace('\'', "''"))
+        }
+        (_, saphyr_parser::ScalarStyle::Plain) if is_plain_safe(value) => value.to_owned(),
+        (_, saphyr_parser::ScalarStyle::Plain) => serde_json::to_string(value)?,
+        (_, saphyr_parser::ScalarStyle::Literal | saphyr_parser::ScalarStyle::Folded) => {
+            anyhow::bail!("block scalars are not supported")
+        }
+    };
+
+    // Markers count chars, not bytes
+    let byte_offset = |index: usize| {
+        data.char_indices()
+            .nth(index)
+            .map(|(i, _)| i)
+            .unwrap_or(data.len())
+    };
+    let start = byte_offset(span.start.index());
+    let end = byte_offset(span.end.index());
+    let mut replaced = String::with_capacity(data.len() + rendered.len());
+    replaced.push_str(&data[..start]);
+    replaced.push_str(&rendered);
+    replaced.push_str(&data[end..]);
+    Ok(replaced)
+}
+
+/// Whether `value` can be written as a plain YAML scalar without changing its meaning
+fn is_plain_safe(value: &str) -> bool {
+    const INDICATORS: &[char] = &[
+        '-', '?', ':', ',', '[', ']', '{', '}', '#', '&', '*', '!', '|', '>', '\'', '"', '%', '@',
+        '`',
+    ];
+    !value.is_empty()
+        && !value.starts_with(INDICATORS)
+        && value.trim() == value
+        && !value.contains(['\n', '\r'])
+        && !value.contains(": ")
+        && !value.contains(" #")
+}
+
+type YamlEvents<'i> =
+    std::iter::Peekable<std::vec::IntoIter<(saphyr_parser::Event<'i>, saphyr_parser::Span)>>;
+
+fn is_node_start(event: &saphyr_parser::Event<'_>) -> bool {
+    matches!(
+        event,
+        saphyr_parser::Event::Scalar(..)
+            | saphyr_parser::Event::SequenceStart(..)
+            | saphyr_parser::Event::MappingStart(..)
+            | saphyr_parser::Event::Alias(..)
+    )
+}
+
+/// Walk the node starting at `events`, returning the scalar at `segments`
+fn find_yaml_scalar(
+    events: &mut YamlEvents<'_>,
+    segments: &[String],
+) -> CargoResult<Option<(saphyr_parser::ScalarStyle, saphyr_parser::Span)>> {
+    let Some((event, span)) = events.next() else {
+        return Ok(None);
+    };
+    let Some((segment, rest)) = segments.split_first() else {
+        return match event {
+            saphyr_parser::Event::Scalar(_, style, _, _) => Ok(Some((style, span))),
+            _ => anyhow::bail!("key is not a scalar value"),
+        };
+    };
+    match event {
+        saphyr_parser::Event::MappingStart(..) => loop {
+            if is_yaml_end(events) {
+                return Ok(None);
+            }
+            let (key, _) = events.next().expect("checked for end");
+            if matches!(&key, saphyr_parser::Event::Scalar(k, ..) if k == segment.as_str()) {
+                return find_yaml_scalar(events, rest);
+            }
+            skip_yaml_rest(events, &key);
+            skip_yaml_node(events);
+        },
+        saphyr_parser::Event::SequenceStart(..) => {
+            let Ok(index) = segment.parse::<usize>() else {
+                return Ok(None);
+            };
+            for _ in 0..index {
+                if is_yaml_end(events) {
+                    return Ok(None);
+                }
+                skip_yaml_node(events);
+            }
+            if is_yaml_end(events) {
+                return Ok(None);
+            }
+            find_yaml_scalar(events, rest)
+        }
+        _ => Ok(None),
+    }
+}
 
-        const PREFIX: &str = "{{prefix}}";
-        const TAG_NAME: &str = "{{tag_name}}";
+/// Whether the current mapping or sequence has no more entries
+fn is_yaml_end(events: &mut YamlEvents<'_>) -> bool {
+    events.peek().is_none_or(|(e, _)| {
+        matches!(
+            e,
+            saphyr_parser::Event::MappingEnd | saphyr_parser::Event::SequenceEnd
+        )
+    })
+}
 
-        let mut s = input.to_owned();
-        s = render_var(s, PREV_VERSION, self.prev_version);
-        s = render_var(s, PREV_METADATA, self.prev_metadata);
-        s = render_var(s, VERSION, self.version);
-        s = render_var(s, METADATA, self.metadata);
-        s = render_var(s, CRATE_NAME, self.crate_name);
-        s = render_var(s, REPOSITORY, self.repository);
-        s = render_var(s, DATE, self.date);
+fn skip_yaml_node(events: &mut YamlEvents<'_>) {
+    if let Some((event, _)) = events.next() {
+        skip_yaml_rest(events, &event);
+    }
+}
 
-        s = render_var(s, PREFIX, self.prefix);
-        s = render_var(s, TAG_NAME, self.tag_name);
-        s
+/// Skip the remainder of the node that started with `start`
+fn skip_yaml_rest(events: &mut YamlEvents<'_>, start: &saphyr_parser::Event<'_>) {
+    if !matches!(
+        start,
+        saphyr_parser::Event::MappingStart(..) | saphyr_parser::Event::SequenceStart(..)
+    ) {
+        return;
+    }
+    let mut depth = 1;
+    while depth != 0 {
+        match events.next() {
+            Some((
+                saphyr_parser::Event::MappingStart(..) | saphyr_parser::Event::SequenceStart(..),
+                _,
+            )) => {
+                depth += 1;
+            }
+            Some((saphyr_parser::Event::MappingEnd | saphyr_parser::Event::SequenceEnd, _)) => {
+                depth -= 1;
+            }
+            Some(_) => {}
+            None => return,
+        }
     }
 }
 
-fn render_var(mut template: String, var_name: &str, var_value: Option<&str>) -> String {
-    if let Some(var_value) = var_value {
-        template = template.replace(var_name, var_value);
-    } else if template.contains(var_name) {
-        log::warn!("Unrendered {var_name} present in template {template:?}");
+/// Whether `file` should be matched as a glob, rather than a literal path
+fn is_glob(file: &Path) -> bool {
+    file.to_str()
+        .is_some_and(|f| f.contains(['*', '?', '[', '{']))
+}
+
+/// Resolve `file` to the paths, relative to `cwd`, it refers to
+fn expand_file(cwd: &Path, file: &Path) -> CargoResult<Vec<PathBuf>> {
+    if !is_glob(file) {
+        if !cwd.join(file).exists() {
+            anyhow::bail!(
+                "unable to find file {} to perform replace",
+                cwd.join(file).display()
+            );
+        }
+        return Ok(vec![file.to_owned()]);
     }
-    template
+
+    let pattern = file.to_str().expect("globs are UTF-8");
+    let glob = globset::GlobBuilder::new(pattern)
+        .literal_separator(true)
+        .build()?
+        .compile_matcher();
+    let mut paths = Vec::new();
+    // Hidden and ignored files can be release artifacts too, only skip what is never one
+    let walk = ignore::WalkBuilder::new(cwd)
+        .standard_filters(false)
+        .hidden(false)
+        .filter_entry(|entry| {
+            let is_dir = entry.file_type().is_some_and(|t| t.is_dir());
+            !(is_dir && (entry.file_name() == ".git" || entry.file_name() == "target"))
+        })
+        .build();
+    for entry in walk {
+        let entry = entry?;
+        if !entry.file_type().is_some_and(|t| t.is_file()) {
+            continue;
+        }
+        let Ok(path) = entry.path().strip_prefix(cwd) else {
+            continue;
+        };
+        if glob.is_match(path) {
+            paths.push(path.to_owned());
+        }
+    }
+    if paths.is_empty() {
+        anyhow::bail!(
+            "no files match {} to perform replace",
+            cwd.join(file).display()
+        );
+    }
+    paths.sort();
+    Ok(paths)
+}
+
+fn check_count(
+    pattern: &str,
+    file: &Path,
+    min: usize,
+    max: usize,
+    actual: usize,
+) -> CargoResult<()> {
+    if actual < min {
+        anyhow::bail!(
+            "for `{}` in '{}', at least {} replacements expected, found {}",
+            pattern,
+            file.display(),
+            min,
+            actual
+        );
+    } else if max < actual {
+        anyhow::bail!(
+            "for `{}` in '{}', at most {} replacements expected, found {}",
+            pattern,
+            file.display(),
+            max,
+            actual
+        );
+    }
+    Ok(())
 }
 
 pub fn do_file_replacements(
@@ -71,56 +543,87 @@ pub fn do_file_replacements(
 ) -> CargoResult<bool> {
     // Since we don't have a convenient insert-order map, let's do sorted, rather than random.
     let mut by_file = BTreeMap::new();
-    for replace in replace_config {
-        let file = replace.file.clone();
-        by_file.entry(file).or_insert_with(Vec::new).push(replace);
+    for (i, replace) in replace_config.iter().enumerate() {
+        for file in expand_file(cwd, &replace.file)? {
+            by_file
+                .entry(file)
+                .or_insert_with(Vec::new)
+                .push((i, replace));
+        }
     }
 
+    // Check every file before writing any, so a failed count doesn't leave a partial replace
+    let mut totals = vec![0; replace_config.len()];
+    let mut results = Vec::new();
     for (path, replaces) in by_file {
         let file = cwd.join(&path);
         log::debug!("processing replacements for file {}", file.display());
-        if !file.exists() {
-            anyhow::bail!("unable to find file {} to perform replace", file.display());
-        }
         let data = std::fs::read_to_string(&file)?;
         let mut replaced = data.clone();
 
-        for replace in replaces {
-            if prerelease && !replace.prerelease {
-                log::debug!("pre-release, not replacing {}", replace.search);
-                continue;
-            }
+        for (i, replace) in replaces {
+            match (replace.search.as_deref(), replace.key.as_deref()) {
+                (Some(pattern), None) => {
+                    if prerelease && !replace.prerelease {
+                        log::debug!("pre-release, not replacing {pattern}");
+                        continue;
+                    }
+
+                    let r = regex::RegexBuilder::new(pattern).multi_line(true).build()?;
+
+                    let actual = r.find_iter(&replaced).count();
+                    match replace.counts {
+                        ReplaceCounts::PerFile => {
+                            let min = replace.min.or(replace.exactly).unwrap_or(1);
+                            let max = replace.max.or(replace.exactly).unwrap_or(usize::MAX);
+                            check_count(pattern, &path, min, max, actual)?;
+                        }
+                        ReplaceCounts::Total => {
+                            totals[i] += actual;
+                        }
+                    }
+
+                    let to_replace = replace.replace.as_str();
+                    let replacer = template.render(to_replace);
 
-            let pattern = replace.search.as_str();
-            let r = regex::RegexBuilder::new(pattern).multi_line(true).build()?;
+                    replaced = r.replace_all(&replaced, replacer.as_str()).into_owned();
+                }
+                (None, Some(key)) => {
+                    if prerelease && !replace.prerelease {
+                        log::debug!("pre-release, not replacing {key}");
+                        continue;
+                    }
 
-            let min = replace.min.or(replace.exactly).unwrap_or(1);
-            let max = replace.max.or(replace.exactly).unwrap_or(usize::MAX);
-            let actual = r.find_iter(&replaced).count();
-            if actual < min {
-                anyhow::bail!(
-                    "for `{}` in '{}', at least {} replacements expected, found {}",
-                    pattern,
-                    path.display(),
-                    min,
-                    actual
-                );
-            } else if max < actual {
-                anyhow::bail!(
-                    "for `{}` in '{}', at most {} replacements expected, found {}",
-                    pattern,
-                    path.display(),
-                    max,
-                    actual
-                );
+                    let value = template.render(replace.replace.as_str());
+                    replaced = set_key(&replaced, &path, key, &value).map_err(|e| {
+                        anyhow::format_err!("for `{}` in '{}', {}", key, path.display(), e)
+                    })?;
+                }
+                _ => {
+                    anyhow::bail!(
+                        "for replacement in '{}', exactly one of `search` or `key` must be set",
+                        path.display()
+                    );
+                }
             }
+        }
 
-            let to_replace = replace.replace.as_str();
-            let replacer = template.render(to_replace);
+        results.push((path, file, data, replaced));
+    }
 
-            replaced = r.replace_all(&replaced, replacer.as_str()).into_owned();
+    for (replace, actual) in replace_config.iter().zip(totals) {
+        let Some(pattern) = replace.search.as_deref() else {
+            continue;
+        };
+        if replace.counts != ReplaceCounts::Total || (prerelease && !replace.prerelease) {
+            continue;
         }
+        let min = replace.min.or(replace.exactly).unwrap_or(1);
+        let max = replace.max.or(replace.exactly).unwrap_or(usize::MAX);
+        check_count(pattern, &replace.file, min, max, actual)?;
+    }
 
+    for (path, file, data, replaced) in results {
         if data != replaced {
             if dry_run {
                 if noisy {
@@ -145,3 +648,221 @@ pub fn do_file_replacements(
     }
     Ok(true)
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    mod render {
+        use super::*;
+
+        fn template() -> Template<'static> {
+            Template {
+                version: Some("1.2.3-alpha.1"),
+                crate_name: Some("cargo-release"),
+                date: Some("2024-03-09"),
+                ..Default::default()
+            }
+        }
+
+        #[test]
+        fn literal() {
+            assert_eq!(template().render("v{{version}}"), "v1.2.3-alpha.1");
+            assert_eq!(template().render("v{{ version }}"), "v1.2.3-alpha.1");
+        }
+
+        #[test]
+        fn components() {
+            assert_eq!(
+                template().render("{{version.major}}.{{version.minor}}-{{version.pre}}"),
+                "1.2-alpha.1"
+            );
+        }
+
+        #[test]
+        fn filters() {
+            assert_eq!(
+                template()
+                    .render(r#"{{crate_name | snake_case | upper}} {{date | date: "%Y%m%d"}}"#),
+                "CARGO_RELEASE 20240309"
+            );
+        }
+
+        #[test]
+        fn prerelease() {
+            let input = "{{#if prerelease}}beta{{else}}stable{{/if}}";
+            assert_eq!(template().render(input), "beta");
+            let stable = Template {
+                version: Some("1.2.3"),
+                ..Default::default()
+            };
+            assert_eq!(stable.render(input), "stable");
+        }
+
+        #[test]
+        fn unrendered() {
+            assert_eq!(template().render("{{tag_name}}"), "{{tag_name}}");
+            assert_eq!(template().render("{{version"), "{{version");
+        }
+
+        #[test]
+        fn optional() {
+            let input = "{{#if prev_tag}}{{prev_tag}}..{{/if}}HEAD{{branch}}";
+            assert_eq!(template().render(input), "HEAD");
+            let template = Template {
+                prev_tag: Some("v1.2.2"),
+                ..template()
+            };
+            assert_eq!(template.render(input), "v1.2.2..HEAD");
+        }
+
+        #[test]
+        fn glob() {
+            let template = Template {
+                version: Some("*"),
+                ..Default::default()
+            };
+            assert_eq!(template.render("v{{version.major}}"), "v*");
+        }
+    }
+
+    mod parse_key {
+        use super::*;
+
+        #[test]
+        fn dotted() {
+            assert_eq!(
+                parse_key(r#"dependencies."my.crate".version"#).unwrap(),
+                ["dependencies", "my.crate", "version"]
+            );
+        }
+
+        #[test]
+        fn empty_segment() {
+            assert!(parse_key("package..version").is_err());
+        }
+    }
+
+    mod set_key {
+        use super::*;
+
+        #[test]
+        fn toml() {
+            let data = r#"[dependencies]
+my-crate = { version = "0.1.0", path = "../my-crate" } # pinned
+"#;
+            let actual = set_key(
+                data,
+                Path::new("Cargo.toml"),
+                r#"dependencies."my-crate".version"#,
+                "0.2.0",
+            )
+            .unwrap();
+            assert_eq!(
+                actual,
+                r#"[dependencies]
+my-crate = { version = "0.2.0", path = "../my-crate" } # pinned
+"#
+            );
+        }
+
+        #[test]
+        fn toml_keeps_quoting() {
+            let data = r#"a = 'C:\old'
+b = """
+old"""
+c = "old"
+"#;
+            let actual = set_key(data, Path::new("a.toml"), "a", r"C:\new").unwrap();
+            let actual = set_key(&actual, Path::new("a.toml"), "b", "new\n\"line\"").unwrap();
+            let actual = set_key(&actual, Path::new("a.toml"), "c", "tab\there").unwrap();
+            assert_eq!(
+                actual,
+                r#"a = 'C:\new'
+b = """new
+\"line\""""
+c = "tab\there"
+"#
+            );
+            assert!(set_key(data, Path::new("a.toml"), "a", "it's").is_err());
+        }
+
+        #[test]
+        fn toml_keeps_type() {
+            let data = "count = 1\nenabled = true\nlist = [1]\n";
+            let actual = set_key(data, Path::new("a.toml"), "count", "2").unwrap();
+            let actual = set_key(&actual, Path::new("a.toml"), "enabled", "false").unwrap();
+            assert_eq!(actual, "count = 2\nenabled = false\nlist = [1]\n");
+            assert!(set_key(data, Path::new("a.toml"), "count", "0.2.0").is_err());
+            assert!(set_key(data, Path::new("a.toml"), "count", "\"2\"").is_err());
+            assert!(set_key(data, Path::new("a.toml"), "enabled", "yes").is_err());
+            assert!(set_key(data, Path::new("a.toml"), "list", "[2]").is_err());
+        }
+
+        #[test]
+        fn json() {
+            let data = r#"{
+  "name": "ünïcode",
+  "version": "0.1.0",
+  "files": ["a", "b"]
+}
+"#;
+            let actual = set_key(data, Path::new("package.json"), "version", "0.2.0").unwrap();
+            assert_eq!(
+                actual,
+                r#"{
+  "name": "ünïcode",
+  "version": "0.2.0",
+  "files": ["a", "b"]
+}
+"#
+            );
+            let actual = set_key(data, Path::new("package.json"), "files.1", "c").unwrap();
+            assert!(actual.contains(r#"["a", "c"]"#), "{actual}");
+        }
+
+        #[test]
+        fn yaml() {
+            let data = r#"# Chart
+name: chart
+version: 0.1.0
+appVersion: "0.1.0"
+dependencies:
+  - name: common
+    version: '0.1.0'
+"#;
+            let actual = set_key(data, Path::new("Chart.yaml"), "version", "0.2.0").unwrap();
+            let actual = set_key(&actual, Path::new("Chart.yaml"), "appVersion", "0.2.0").unwrap();
+            let actual = set_key(
+                &actual,
+                Path::new("Chart.yaml"),
+                "dependencies.0.version",
+                "0.2.0",
+            )
+            .unwrap();
+            assert_eq!(
+                actual,
+                r#"# Chart
+name: chart
+version: 0.2.0
+appVersion: "0.2.0"
+dependencies:
+  - name: common
+    version: '0.2.0'
+"#
+            );
+        }
+
+        #[test]
+        fn missing_key() {
+            assert!(set_key("version = 1", Path::new("a.toml"), "package.version", "2").is_err());
+            assert!(set_key(r#"{"a": {}}"#, Path::new("a.json"), "a.b", "2").is_err());
+            assert!(set_key("a:\n  c: 1\n", Path::new("a.yml"), "a.b", "2").is_err());
+        }
+
+        #[test]
+        fn unsupported_format() {
+            assert!(set_key("", Path::new("README.md"), "version", "2").is_err());
+        }
+    }
+}
@@ -0,0 +1,518 @@
+//! Parsing for the `{{...}}` placeholders in config strings
+//!
+//! Supported syntax:
+//! - `{{name}}` or `{{ name }}`: a variable, like `version` or `version.major`
+//! - `{{name | filter | filter: "arg"}}`: a variable transformed by filters
+//! - `{{#if name}}...{{else}}...{{/if}}`: text included depending on whether a variable is set
+
+use std::ops::Range;
+
+/// A malformed template
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TemplateError {
+    pub message: String,
+    /// Byte range within the template
+    pub span: Range<usize>,
+}
+
+impl TemplateError {
+    fn new(message: impl Into<String>, span: Range<usize>) -> Self {
+        Self {
+            message: message.into(),
+            span,
+        }
+    }
+}
+
+impl std::fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.message.fmt(f)
+    }
+}
+
+impl std::error::Error for TemplateError {}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Node<'t> {
+    Text(&'t str),
+    Var(Var<'t>),
+    If {
+        cond: Var<'t>,
+        then: Vec<Self>,
+        otherwise: Vec<Self>,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Var<'t> {
+    /// The full `{{...}}` text, for leaving unrendered
+    pub raw: &'t str,
+    pub name: &'t str,
+    /// Byte range of `name` within the template
+    pub span: Range<usize>,
+    pub filters: Vec<Filter<'t>>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Filter<'t> {
+    Upper,
+    Lower,
+    SnakeCase,
+    KebabCase,
+    /// Reformat a `YYYY-MM-DD` date with `strftime`-style specifiers
+    Date(&'t str),
+}
+
+impl Filter<'_> {
+    pub fn apply(&self, value: &str) -> Option<String> {
+        match self {
+            Self::Upper => Some(value.to_uppercase()),
+            Self::Lower => Some(value.to_lowercase()),
+            Self::SnakeCase => Some(split_case(value, '_')),
+            Self::KebabCase => Some(split_case(value, '-')),
+            Self::Date(format) => format_date(value, format),
+        }
+    }
+}
+
+/// An `#if` whose `/if` hasn't been reached yet
+struct Block<'t> {
+    cond: Var<'t>,
+    /// The nodes before the `#if`
+    outer: Vec<Node<'t>>,
+    /// The nodes before the `else`, if reached
+    then: Option<Vec<Node<'t>>>,
+    start: usize,
+}
+
+/// Every variable a template may reference, though not all are available everywhere
+pub const VARIABLES: &[&str] = &[
+    "prev_version",
+    "prev_metadata",
+    "version",
+    "metadata",
+    "crate_name",
+    "repository",
+    "date",
+    "next_major",
+    "next_minor",
+    "next_patch",
+    "branch",
+    "head_sha",
+    "prev_tag",
+    "registry",
+    "prefix",
+    "tag_name",
+];
+
+/// Variables that render empty rather than being left in when unset
+pub const OPTIONAL_VARIABLES: &[&str] = &["branch", "prev_tag"];
+
+/// Variables with `.major`, `.minor`, `.patch`, `.pre`, and `.build` components
+pub const VERSION_VARIABLES: &[&str] = &[
+    "prev_version",
+    "version",
+    "next_major",
+    "next_minor",
+    "next_patch",
+];
+
+const VERSION_COMPONENTS: &[&str] = &["major", "minor", "patch", "pre", "build"];
+
+/// Check that `template` is well-formed and only uses `available` variables
+pub fn validate(template: &str, available: &[&str]) -> Result<(), TemplateError> {
+    let nodes = parse(template)?;
+    validate_nodes(&nodes, available)
+}
+
+fn validate_nodes(nodes: &[Node<'_>], available: &[&str]) -> Result<(), TemplateError> {
+    for node in nodes {
+        match node {
+            Node::Text(_) => {}
+            Node::Var(var) => validate_var(var, available)?,
+            Node::If {
+                cond,
+                then,
+                otherwise,
+            } => {
+                validate_var(cond, available)?;
+                validate_nodes(then, available)?;
+                validate_nodes(otherwise, available)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn validate_var(var: &Var<'_>, available: &[&str]) -> Result<(), TemplateError> {
+    let (base, component) = match var.name.split_once('.') {
+        Some((base, component)) => (base, Some(component)),
+        None => (var.name, None),
+    };
+    // `prerelease` is derived from `version`
+    let required = if base == "prerelease" && component.is_none() {
+        "version"
+    } else if VARIABLES.contains(&base) {
+        base
+    } else {
+        return Err(TemplateError::new(
+            format!("unknown placeholder `{}`", var.name),
+            var.span.clone(),
+        ));
+    };
+    if let Some(component) = component {
+        if !VERSION_VARIABLES.contains(&base) {
+            return Err(TemplateError::new(
+                format!("`{base}` has no `.{component}`"),
+                var.span.clone(),
+            ));
+        }
+        if !VERSION_COMPONENTS.contains(&component) {
+            return Err(TemplateError::new(
+                format!(
+                    "unknown version component `{component}`, expected one of {}",
+                    VERSION_COMPONENTS.join(", ")
+                ),
+                var.span.clone(),
+            ));
+        }
+    }
+    if !available.contains(&required) {
+        return Err(TemplateError::new(
+            format!("`{}` is not available here", var.name),
+            var.span.clone(),
+        ));
+    }
+    Ok(())
+}
+
+pub fn parse(template: &str) -> Result<Vec<Node<'_>>, TemplateError> {
+    let mut blocks: Vec<Block<'_>> = Vec::new();
+    let mut nodes = Vec::new();
+    let mut pos = 0;
+    while let Some(offset) = template[pos..].find("{{") {
+        let start = pos + offset;
+        let Some(len) = template[start..].find("}}") else {
+            return Err(TemplateError::new("unclosed `{{`", start..template.len()));
+        };
+        let end = start + len + 2;
+        if pos < start {
+            nodes.push(Node::Text(&template[pos..start]));
+        }
+        pos = end;
+
+        let inner_start = start + 2;
+        let inner = &template[inner_start..end - 2];
+        let trimmed = inner.trim();
+        let trimmed_start = inner_start + (inner.len() - inner.trim_start().len());
+        if let Some(cond) = trimmed.strip_prefix("#if") {
+            let cond_start = trimmed_start + 3 + (cond.len() - cond.trim_start().len());
+            if cond.trim().is_empty() || !cond.starts_with(char::is_whitespace) {
+                return Err(TemplateError::new(
+                    "expected a variable after `#if`",
+                    start..end,
+                ));
+            }
+            let cond = parse_var(&template[start..end], cond.trim(), cond_start)?;
+            blocks.push(Block {
+                cond,
+                outer: std::mem::take(&mut nodes),
+                then: None,
+                start,
+            });
+        } else if trimmed == "else" {
+            let Some(Block { then, .. }) = blocks.last_mut() else {
+                return Err(TemplateError::new("`else` outside of `#if`", start..end));
+            };
+            if then.is_some() {
+                return Err(TemplateError::new("duplicate `else`", start..end));
+            }
+            *then = Some(std::mem::take(&mut nodes));
+        } else if trimmed == "/if" {
+            let Some(Block {
+                cond, outer, then, ..
+            }) = blocks.pop()
+            else {
+                return Err(TemplateError::new("`/if` without `#if`", start..end));
+            };
+            let body = std::mem::replace(&mut nodes, outer);
+            let (then, otherwise) = match then {
+                Some(then) => (then, body),
+                None => (body, Vec::new()),
+            };
+            nodes.push(Node::If {
+                cond,
+                then,
+                otherwise,
+            });
+        } else {
+            nodes.push(Node::Var(parse_var(
+                &template[start..end],
+                trimmed,
+                trimmed_start,
+            )?));
+        }
+    }
+    if let Some(Block { start, .. }) = blocks.pop() {
+        return Err(TemplateError::new(
+            "`#if` without `/if`",
+            start..template.len(),
+        ));
+    }
+    if pos < template.len() {
+        nodes.push(Node::Text(&template[pos..]));
+    }
+    Ok(nodes)
+}
+
+fn parse_var<'t>(raw: &'t str, expr: &'t str, offset: usize) -> Result<Var<'t>, TemplateError> {
+    let mut parts = split_filters(expr).into_iter();
+    let (name_offset, name) = parts.next().expect("split always yields one part");
+    let name_start = offset + name_offset + (name.len() - name.trim_start().len());
+    let name = name.trim();
+    let span = name_start..name_start + name.len();
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+    {
+        return Err(TemplateError::new(
+            format!("invalid variable `{name}`"),
+            span,
+        ));
+    }
+    let filters = parts
+        .map(|(filter_offset, filter)| {
+            let filter_start = offset + filter_offset + (filter.len() - filter.trim_start().len());
+            let filter = filter.trim();
+            parse_filter(filter, filter_start..filter_start + filter.len())
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(Var {
+        raw,
+        name,
+        span,
+        filters,
+    })
+}
+
+/// Split on `|`, except within quotes, keeping each part's offset
+fn split_filters(expr: &str) -> Vec<(usize, &str)> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    let mut quoted = false;
+    for (i, c) in expr.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            '|' if !quoted => {
+                parts.push((start, &expr[start..i]));
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push((start, &expr[start..]));
+    parts
+}
+
+fn parse_filter(filter: &str, span: Range<usize>) -> Result<Filter<'_>, TemplateError> {
+    let (name, arg) = match filter.split_once(':') {
+        Some((name, arg)) => (name.trim(), Some(arg.trim())),
+        None => (filter.trim(), None),
+    };
+    let arg = arg
+        .map(|arg| {
+            arg.strip_prefix('"')
+                .and_then(|a| a.strip_suffix('"'))
+                .ok_or_else(|| {
+                    TemplateError::new(
+                        format!("argument to `{name}` must be a quoted string"),
+                        span.clone(),
+                    )
+                })
+        })
+        .transpose()?;
+    match (name, arg) {
+        ("upper", None) => Ok(Filter::Upper),
+        ("lower", None) => Ok(Filter::Lower),
+        ("snake_case", None) => Ok(Filter::SnakeCase),
+        ("kebab_case", None) => Ok(Filter::KebabCase),
+        ("date", Some(format)) => Ok(Filter::Date(format)),
+        ("date", None) => Err(TemplateError::new(
+            "`date` requires a format, like `date: \"%Y%m%d\"`",
+            span,
+        )),
+        ("upper" | "lower" | "snake_case" | "kebab_case", Some(_)) => Err(TemplateError::new(
+            format!("`{name}` takes no argument"),
+            span,
+        )),
+        _ => Err(TemplateError::new(format!("unknown filter `{name}`"), span)),
+    }
+}
+
+/// Split words on separators and case changes, joining them with `sep`
+fn split_case(value: &str, sep: char) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut prev: Option<char> = None;
+    for c in value.chars() {
+        if matches!(c, '-' | '_' | ' ' | '.') {
+            if !out.is_empty() && !out.ends_with(sep) {
+                out.push(sep);
+            }
+        } else {
+            if c.is_uppercase()
+                && prev.is_some_and(|p| p.is_lowercase() || p.is_ascii_digit())
+                && !out.ends_with(sep)
+            {
+                out.push(sep);
+            }
+            out.extend(c.to_lowercase());
+        }
+        prev = Some(c);
+    }
+    out
+}
+
+fn format_date(value: &str, format: &str) -> Option<String> {
+    let mut parts = value.splitn(3, '-');
+    let year = parts.next()?.parse().ok()?;
+    let month = parts.next()?.parse::<u8>().ok()?.try_into().ok()?;
+    let day = parts.next()?.parse().ok()?;
+    let date = time::Date::from_calendar_date(year, month, day).ok()?;
+
+    let mut out = String::new();
+    let mut chars = format.chars();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('Y') => out.push_str(&date.year().to_string()),
+            Some('y') => out.push_str(&format!("{:02}", date.year() % 100)),
+            Some('m') => out.push_str(&format!("{:02}", u8::from(date.month()))),
+            Some('d') => out.push_str(&format!("{:02}", date.day())),
+            Some('e') => out.push_str(&date.day().to_string()),
+            Some('B') => out.push_str(&date.month().to_string()),
+            Some('b') => out.push_str(&date.month().to_string()[..3]),
+            Some('j') => out.push_str(&format!("{:03}", date.ordinal())),
+            Some('%') => out.push('%'),
+            Some(other) => {
+                out.push('%');
+                out.push(other);
+            }
+            None => out.push('%'),
+        }
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    mod parse {
+        use super::*;
+
+        #[test]
+        fn text() {
+            assert_eq!(parse("v1.0").unwrap(), [Node::Text("v1.0")]);
+        }
+
+        #[test]
+        fn var_with_filters() {
+            let nodes = parse(r#"{{ crate_name | upper | date: "%Y" }}"#).unwrap();
+            let [Node::Var(var)] = nodes.as_slice() else {
+                panic!("{nodes:?}");
+            };
+            assert_eq!(var.name, "crate_name");
+            assert_eq!(var.span, 3..13);
+            assert_eq!(var.filters, [Filter::Upper, Filter::Date("%Y")]);
+        }
+
+        #[test]
+        fn if_else() {
+            let nodes = parse("a{{#if prerelease}}b{{else}}c{{/if}}").unwrap();
+            let [
+                Node::Text("a"),
+                Node::If {
+                    cond,
+                    then,
+                    otherwise,
+                },
+            ] = nodes.as_slice()
+            else {
+                panic!("{nodes:?}");
+            };
+            assert_eq!(cond.name, "prerelease");
+            assert_eq!(then, &[Node::Text("b")]);
+            assert_eq!(otherwise, &[Node::Text("c")]);
+        }
+
+        #[test]
+        fn errors() {
+            assert_eq!(parse("{{version").unwrap_err().span, 0..9);
+            assert_eq!(
+                parse("{{#if prerelease}}").unwrap_err().message,
+                "`#if` without `/if`"
+            );
+            assert_eq!(parse("{{/if}}").unwrap_err().message, "`/if` without `#if`");
+            let err = parse("v{{ version | shout }}").unwrap_err();
+            assert_eq!(err.message, "unknown filter `shout`");
+            assert_eq!(err.span, 14..19);
+            let err = parse("{{ver sion}}").unwrap_err();
+            assert_eq!(err.span, 2..10);
+        }
+    }
+
+    mod validate {
+        use super::*;
+
+        #[test]
+        fn valid() {
+            validate(
+                "{{#if prerelease}}{{version.major}}{{/if}} {{crate_name | upper}}",
+                &["version", "crate_name"],
+            )
+            .unwrap();
+        }
+
+        #[test]
+        fn unknown() {
+            let err = validate("{{crate-name}}", VARIABLES).unwrap_err();
+            assert_eq!(err.message, "unknown placeholder `crate-name`");
+            assert_eq!(err.span, 2..12);
+            let err = validate("{{version.minr}}", VARIABLES).unwrap_err();
+            assert_eq!(err.span, 2..14);
+            assert!(validate("{{crate_name.major}}", VARIABLES).is_err());
+        }
+
+        #[test]
+        fn not_available() {
+            let err = validate("Release {{ crate_name }}", &["version"]).unwrap_err();
+            assert_eq!(err.message, "`crate_name` is not available here");
+            assert_eq!(err.span, 11..21);
+            assert!(validate("{{#if prerelease}}beta{{/if}}", &["date"]).is_err());
+        }
+    }
+
+    mod filter {
+        use super::*;
+
+        #[test]
+        fn case() {
+            assert_eq!(split_case("cargo-release", '_'), "cargo_release");
+            assert_eq!(split_case("CargoRelease", '_'), "cargo_release");
+            assert_eq!(split_case("cargo_release", '-'), "cargo-release");
+        }
+
+        #[test]
+        fn date() {
+            assert_eq!(
+                format_date("2024-03-09", "%Y%m%d %y %e %b %B %j %%").unwrap(),
+                "20240309 24 9 Mar March 069 %"
+            );
+            assert_eq!(format_date("*", "%Y"), None);
+        }
+    }
+}
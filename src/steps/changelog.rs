@@ -0,0 +1,254 @@
+use std::fmt::Write as _;
+
+use crate::error::CliError;
+use crate::ops::git;
+use crate::ops::replace::NOW;
+use crate::steps::changes;
+use crate::steps::plan;
+
+const HEADER: &str = "# Changelog
+
+All notable changes to this project will be documented in this file.
+
+The format is based on [Keep a Changelog](https://keepachangelog.com/en/1.1.0/),
+and this project adheres to [Semantic Versioning](https://semver.org/spec/v2.0.0.html).
+";
+
+/// Order of the sections within a release
+const GROUPS: [&str; 5] = ["Added", "Changed", "Fixed", "Performance", "Documentation"];
+
+/// Add a section for the new version to the package's changelog
+pub fn changelog(
+    ws_meta: &cargo_metadata::Metadata,
+    pkg: &plan::PackageRelease,
+    dry_run: bool,
+) -> Result<(), CliError> {
+    let Some(changelog_path) = pkg.config.changelog() else {
+        return Ok(());
+    };
+    let crate_name = pkg.meta.name.as_str();
+    let Some(prior_tag_name) = pkg.prior_tag.as_deref() else {
+        log::debug!(
+            "cannot update the changelog for {crate_name} because no tag was found. Try setting `--prev-tag-name <TAG>`."
+        );
+        return Ok(());
+    };
+
+    let workspace_root = ws_meta.workspace_root.as_std_path();
+    let commits = changes::package_commits(workspace_root, pkg, prior_tag_name)?;
+    let version = pkg.planned_version.as_ref().unwrap_or(&pkg.initial_version);
+    let Some(section) = render_section(&version.full_version_string, NOW.as_str(), &commits) else {
+        log::debug!("no changes for {crate_name} to add to the changelog since {prior_tag_name}");
+        return Ok(());
+    };
+
+    let file = pkg.package_root.join(changelog_path);
+    let exists = file.exists();
+    let data = if exists {
+        std::fs::read_to_string(&file)?
+    } else {
+        String::new()
+    };
+    let updated = insert_section(&data, &section);
+    if dry_run {
+        let _ = crate::ops::shell::status(
+            "Updating",
+            format!(
+                "{}\n{}",
+                changelog_path.display(),
+                crate::ops::diff::unified_diff(&data, &updated, changelog_path, "updated")
+            ),
+        );
+    } else {
+        std::fs::write(&file, updated)?;
+        // `commit_all` only picks up tracked files
+        if !exists && !git::add(&pkg.package_root, &file, dry_run)? {
+            return Err(101.into());
+        }
+    }
+
+    Ok(())
+}
+
+/// Render a Keep a Changelog section for `version` from `commits`
+///
+/// Returns `None` if none of the commits are relevant to users.
+pub fn render_section(
+    version: &str,
+    date: &str,
+    commits: &[changes::PackageCommit],
+) -> Option<String> {
+    let mut entries = commits.iter().filter_map(entry).collect::<Vec<_>>();
+    if entries.is_empty() {
+        return None;
+    }
+    // Highlight breaking changes by listing them first
+    entries.sort_by_key(|(_, breaking, _)| !breaking);
+
+    let mut section = String::new();
+    writeln!(&mut section, "## [{version}] - {date}").unwrap();
+    for group in GROUPS {
+        let mut group_entries = entries.iter().filter(|(g, _, _)| *g == group).peekable();
+        if group_entries.peek().is_none() {
+            continue;
+        }
+        writeln!(&mut section, "\n### {group}\n").unwrap();
+        for (_, _, entry) in group_entries {
+            writeln!(&mut section, "- {entry}").unwrap();
+        }
+    }
+    Some(section)
+}
+
+fn entry(commit: &changes::PackageCommit) -> Option<(&'static str, bool, String)> {
+    if commit.status() == Some(changes::CommitStatus::Ignore) {
+        return None;
+    }
+    let Ok(parts) = git_conventional::Commit::parse(&commit.message) else {
+        return Some(("Changed", false, commit.summary.clone()));
+    };
+
+    let type_ = parts.type_();
+    let group = if type_ == git_conventional::Type::FEAT {
+        "Added"
+    } else if type_ == git_conventional::Type::FIX {
+        "Fixed"
+    } else if type_ == git_conventional::Type::PERF {
+        "Performance"
+    } else if type_ == git_conventional::Type::DOCS {
+        "Documentation"
+    } else {
+        "Changed"
+    };
+    let breaking = parts.breaking();
+    let mut entry = String::new();
+    if breaking {
+        entry.push_str("**Breaking:** ");
+    }
+    if let Some(scope) = parts.scope() {
+        write!(&mut entry, "{scope}: ").unwrap();
+    }
+    entry.push_str(parts.description());
+    Some((group, breaking, entry))
+}
+
+/// Insert `section` before the latest release in `changelog`
+pub fn insert_section(changelog: &str, section: &str) -> String {
+    if changelog.trim().is_empty() {
+        return format!("{HEADER}\n{section}");
+    }
+
+    let mut offset = 0;
+    for line in changelog.split_inclusive('\n') {
+        if let Some(heading) = line.strip_prefix("## ")
+            && !heading.to_lowercase().contains("unreleased")
+        {
+            let (before, after) = changelog.split_at(offset);
+            return format!("{before}{section}\n{after}");
+        }
+        offset += line.len();
+    }
+
+    let mut changelog = changelog.to_owned();
+    if !changelog.ends_with('\n') {
+        changelog.push('\n');
+    }
+    changelog.push('\n');
+    changelog.push_str(section);
+    changelog
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn commit(message: &str) -> changes::PackageCommit {
+        changes::PackageCommit {
+            id: git2::Oid::zero(),
+            short_id: "0000000".to_owned(),
+            summary: message.lines().next().unwrap().to_owned(),
+            message: message.to_owned(),
+            paths: Default::default(),
+        }
+    }
+
+    mod render_section {
+        use super::*;
+
+        #[test]
+        fn groups() {
+            let commits = [
+                commit("fix(parser): Handle empty input"),
+                commit("chore: Update dependencies"),
+                commit("feat: Add `--all` flag"),
+                commit("Tweak the output"),
+                commit("feat!: Remove `--old` flag"),
+            ];
+            let actual = render_section("1.0.0", "2020-01-01", &commits).unwrap();
+            let expected = "\
+## [1.0.0] - 2020-01-01
+
+### Added
+
+- **Breaking:** Remove `--old` flag
+- Add `--all` flag
+
+### Changed
+
+- Tweak the output
+
+### Fixed
+
+- parser: Handle empty input
+";
+            assert_eq!(actual, expected);
+        }
+
+        #[test]
+        fn nothing_relevant() {
+            let commits = [commit("chore: Update dependencies")];
+            assert_eq!(render_section("1.0.0", "2020-01-01", &commits), None);
+        }
+    }
+
+    mod insert_section {
+        use super::*;
+
+        const SECTION: &str = "## [1.1.0] - 2020-02-02\n\n### Fixed\n\n- Bug\n";
+
+        #[test]
+        fn new_file() {
+            let actual = insert_section("", SECTION);
+            assert!(actual.starts_with("# Changelog\n"), "{actual}");
+            assert!(actual.ends_with(SECTION), "{actual}");
+        }
+
+        #[test]
+        fn after_unreleased() {
+            let changelog = "# Changelog\n\n## [Unreleased] - ReleaseDate\n\n## [1.0.0] - 2020-01-01\n\n- Initial\n";
+            let actual = insert_section(changelog, SECTION);
+            let expected = "\
+# Changelog
+
+## [Unreleased] - ReleaseDate
+
+## [1.1.0] - 2020-02-02
+
+### Fixed
+
+- Bug
+
+## [1.0.0] - 2020-01-01
+
+- Initial
+";
+            assert_eq!(actual, expected);
+        }
+
+        #[test]
+        fn no_releases() {
+            let actual = insert_section("# Changelog", SECTION);
+            assert_eq!(actual, format!("# Changelog\n\n{SECTION}"));
+        }
+    }
+}
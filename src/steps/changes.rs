@@ -2,6 +2,7 @@ use crate::error::CargoResult;
 use crate::error::CliError;
 use crate::ops::git;
 use crate::ops::version::VersionExt as _;
+use crate::steps::BumpLevel;
 use crate::steps::plan;
 use clap_cargo::style::{ERROR, GOOD, NOP, WARN};
 
@@ -113,69 +114,7 @@ pub fn changes(
         let crate_name = pkg.meta.name.as_str();
         if let Some(prior_tag_name) = &pkg.prior_tag {
             let workspace_root = ws_meta.workspace_root.as_std_path();
-            let repo = git2::Repository::discover(workspace_root)?;
-
-            let mut tag_id = None;
-            let fq_prior_tag_name = format!("refs/tags/{prior_tag_name}");
-            repo.tag_foreach(|id, name| {
-                if name == fq_prior_tag_name.as_bytes() {
-                    tag_id = Some(id);
-                    false
-                } else {
-                    true
-                }
-            })?;
-            let tag_id =
-                tag_id.ok_or_else(|| anyhow::format_err!("could not find tag {prior_tag_name}"))?;
-
-            let head_id = repo.head()?.peel_to_commit()?.id();
-
-            let mut revwalk = repo.revwalk()?;
-            revwalk.push_range(&format!("{tag_id}..{head_id}"))?;
-
-            let mut commits = Vec::new();
-            for commit_id in revwalk {
-                let commit_id = commit_id?;
-                let commit = repo.find_commit(commit_id)?;
-                if 1 < commit.parent_count() {
-                    // Assuming merge commits can be ignored
-                    continue;
-                }
-                let parent_tree = commit.parent(0).ok().map(|c| c.tree()).transpose()?;
-                let tree = commit.tree()?;
-                let diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), None)?;
-
-                let mut changed_paths = std::collections::BTreeSet::new();
-                for delta in diff.deltas() {
-                    let old_path = delta.old_file().path();
-                    let new_path = delta.new_file().path();
-                    for entry_relpath in [old_path, new_path].into_iter().flatten() {
-                        for path in pkg
-                            .package_content
-                            .iter()
-                            .filter_map(|p| p.strip_prefix(workspace_root).ok())
-                        {
-                            if path == entry_relpath {
-                                changed_paths.insert(path.to_owned());
-                            }
-                        }
-                    }
-                }
-
-                if !changed_paths.is_empty() {
-                    let short_id =
-                        String::from_utf8_lossy(&repo.find_object(commit_id, None)?.short_id()?)
-                            .into_owned();
-                    commits.push(PackageCommit {
-                        id: commit_id,
-                        short_id,
-                        summary: String::from_utf8_lossy(commit.summary_bytes().unwrap_or(b""))
-                            .into_owned(),
-                        message: String::from_utf8_lossy(commit.message_bytes()).into_owned(),
-                        paths: changed_paths,
-                    });
-                }
-            }
+            let commits = package_commits(workspace_root, pkg, prior_tag_name)?;
 
             if !commits.is_empty() {
                 crate::ops::shell::status(
@@ -186,7 +125,6 @@ pub fn changes(
                     ),
                 )?;
                 let prefix = format!("{:>13}", " ");
-                let mut max_status = None;
                 for commit in &commits {
                     let _ = crate::ops::shell::write_stderr(&prefix, &NOP);
                     let _ = crate::ops::shell::write_stderr(&commit.short_id, &WARN);
@@ -196,16 +134,8 @@ pub fn changes(
                     let current_status = commit.status();
                     write_status(current_status);
                     let _ = crate::ops::shell::write_stderr("\n", &NOP);
-                    match (current_status, max_status) {
-                        (Some(cur), Some(max)) => {
-                            max_status = Some(cur.max(max));
-                        }
-                        (Some(s), None) | (None, Some(s)) => {
-                            max_status = Some(s);
-                        }
-                        (None, None) => {}
-                    }
                 }
+                let mut max_status = max_status(&commits);
                 if version.full_version.is_prerelease() {
                     // Enough unknowns about pre-release to not bother
                     max_status = None;
@@ -217,39 +147,7 @@ pub fn changes(
                     .unwrap_or(false);
                 let bumped = !unbumped;
                 if let Some(max_status) = max_status {
-                    let suggested = match max_status {
-                        CommitStatus::Breaking => {
-                            match (
-                                version.full_version.major,
-                                version.full_version.minor,
-                                version.full_version.patch,
-                            ) {
-                                (0, 0, _) if bumped => None,
-                                (0, 0, _) => Some("patch"),
-                                (0, _, 0) if bumped => None,
-                                (0, _, _) => Some("minor"),
-                                (_, 0, 0) if bumped => None,
-                                (_, _, _) => Some("major"),
-                            }
-                        }
-                        CommitStatus::Feature => {
-                            match (
-                                version.full_version.major,
-                                version.full_version.minor,
-                                version.full_version.patch,
-                            ) {
-                                (0, 0, _) if bumped => None,
-                                (0, 0, _) => Some("patch"),
-                                (0, _, _) if bumped => None,
-                                (0, _, _) => Some("patch"),
-                                (_, _, 0) if bumped => None,
-                                (_, _, _) => Some("minor"),
-                            }
-                        }
-                        CommitStatus::Fix if bumped => None,
-                        CommitStatus::Fix => Some("patch"),
-                        CommitStatus::Ignore => None,
-                    };
+                    let suggested = suggested_bump(&version.full_version, max_status, bumped);
                     if let Some(suggested) = suggested {
                         let _ = crate::ops::shell::help(format!(
                             "to update the version, run `cargo release version -p {crate_name} {suggested}`"
@@ -271,6 +169,115 @@ pub fn changes(
     Ok(())
 }
 
+/// Collect the commits since `prior_tag_name` that touch the package's content
+pub fn package_commits(
+    workspace_root: &std::path::Path,
+    pkg: &plan::PackageRelease,
+    prior_tag_name: &str,
+) -> CargoResult<Vec<PackageCommit>> {
+    let repo = git2::Repository::discover(workspace_root)?;
+
+    let mut tag_id = None;
+    let fq_prior_tag_name = format!("refs/tags/{prior_tag_name}");
+    repo.tag_foreach(|id, name| {
+        if name == fq_prior_tag_name.as_bytes() {
+            tag_id = Some(id);
+            false
+        } else {
+            true
+        }
+    })?;
+    let tag_id =
+        tag_id.ok_or_else(|| anyhow::format_err!("could not find tag {prior_tag_name}"))?;
+
+    let head_id = repo.head()?.peel_to_commit()?.id();
+
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push_range(&format!("{tag_id}..{head_id}"))?;
+
+    let mut commits = Vec::new();
+    for commit_id in revwalk {
+        let commit_id = commit_id?;
+        let commit = repo.find_commit(commit_id)?;
+        if 1 < commit.parent_count() {
+            // Assuming merge commits can be ignored
+            continue;
+        }
+        let parent_tree = commit.parent(0).ok().map(|c| c.tree()).transpose()?;
+        let tree = commit.tree()?;
+        let diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), None)?;
+
+        let mut changed_paths = std::collections::BTreeSet::new();
+        for delta in diff.deltas() {
+            let old_path = delta.old_file().path();
+            let new_path = delta.new_file().path();
+            for entry_relpath in [old_path, new_path].into_iter().flatten() {
+                for path in pkg
+                    .package_content
+                    .iter()
+                    .filter_map(|p| p.strip_prefix(workspace_root).ok())
+                {
+                    if path == entry_relpath {
+                        changed_paths.insert(path.to_owned());
+                    }
+                }
+            }
+        }
+
+        if !changed_paths.is_empty() {
+            let short_id = String::from_utf8_lossy(&repo.find_object(commit_id, None)?.short_id()?)
+                .into_owned();
+            commits.push(PackageCommit {
+                id: commit_id,
+                short_id,
+                summary: String::from_utf8_lossy(commit.summary_bytes().unwrap_or(b""))
+                    .into_owned(),
+                message: String::from_utf8_lossy(commit.message_bytes()).into_owned(),
+                paths: changed_paths,
+            });
+        }
+    }
+
+    Ok(commits)
+}
+
+/// The most significant [`CommitStatus`] among `commits`, if any are conventional
+pub fn max_status(commits: &[PackageCommit]) -> Option<CommitStatus> {
+    commits.iter().filter_map(|c| c.status()).max()
+}
+
+/// Suggest the [`BumpLevel`] needed to reflect `max_status`
+///
+/// When `bumped`, `version` is assumed to not be released yet and `None` is returned if it
+/// already accounts for `max_status`.
+pub fn suggested_bump(
+    version: &semver::Version,
+    max_status: CommitStatus,
+    bumped: bool,
+) -> Option<BumpLevel> {
+    match max_status {
+        CommitStatus::Breaking => match (version.major, version.minor, version.patch) {
+            (0, 0, _) if bumped => None,
+            (0, 0, _) => Some(BumpLevel::Patch),
+            (0, _, 0) if bumped => None,
+            (0, _, _) => Some(BumpLevel::Minor),
+            (_, 0, 0) if bumped => None,
+            (_, _, _) => Some(BumpLevel::Major),
+        },
+        CommitStatus::Feature => match (version.major, version.minor, version.patch) {
+            (0, 0, _) if bumped => None,
+            (0, 0, _) => Some(BumpLevel::Patch),
+            (0, _, _) if bumped => None,
+            (0, _, _) => Some(BumpLevel::Patch),
+            (_, _, 0) if bumped => None,
+            (_, _, _) => Some(BumpLevel::Minor),
+        },
+        CommitStatus::Fix if bumped => None,
+        CommitStatus::Fix => Some(BumpLevel::Patch),
+        CommitStatus::Ignore => None,
+    }
+}
+
 fn write_status(status: Option<CommitStatus>) {
     if let Some(status) = status {
         let suffix;
@@ -353,3 +360,62 @@ pub enum CommitStatus {
     Feature,
     Breaking,
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    mod suggested_bump {
+        use super::*;
+
+        fn suggest(version: &str, status: CommitStatus, bumped: bool) -> Option<BumpLevel> {
+            let version = semver::Version::parse(version).unwrap();
+            suggested_bump(&version, status, bumped)
+        }
+
+        #[test]
+        fn breaking() {
+            assert_eq!(
+                suggest("0.0.3", CommitStatus::Breaking, false),
+                Some(BumpLevel::Patch)
+            );
+            assert_eq!(
+                suggest("0.2.3", CommitStatus::Breaking, false),
+                Some(BumpLevel::Minor)
+            );
+            assert_eq!(
+                suggest("1.2.3", CommitStatus::Breaking, false),
+                Some(BumpLevel::Major)
+            );
+            assert_eq!(suggest("0.3.0", CommitStatus::Breaking, true), None);
+            assert_eq!(suggest("2.0.0", CommitStatus::Breaking, true), None);
+        }
+
+        #[test]
+        fn feature() {
+            assert_eq!(
+                suggest("0.2.3", CommitStatus::Feature, false),
+                Some(BumpLevel::Patch)
+            );
+            assert_eq!(
+                suggest("1.2.3", CommitStatus::Feature, false),
+                Some(BumpLevel::Minor)
+            );
+            assert_eq!(suggest("1.3.0", CommitStatus::Feature, true), None);
+        }
+
+        #[test]
+        fn fix() {
+            assert_eq!(
+                suggest("1.2.3", CommitStatus::Fix, false),
+                Some(BumpLevel::Patch)
+            );
+            assert_eq!(suggest("1.2.4", CommitStatus::Fix, true), None);
+        }
+
+        #[test]
+        fn ignore() {
+            assert_eq!(suggest("1.2.3", CommitStatus::Ignore, false), None);
+        }
+    }
+}
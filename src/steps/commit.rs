@@ -1,6 +1,7 @@
 use crate::config;
 use crate::error::CliError;
 use crate::ops::git;
+use crate::ops::index;
 use crate::ops::replace::{NOW, Template};
 use crate::steps::plan;
 
@@ -101,12 +102,25 @@ impl CommitStep {
                     "ignoring `consolidate-commits=false`; `cargo release commit` can effectively only do one commit",
                 );
             }
-            workspace_commit(&ws_meta, &ws_config, &selected_pkgs, dry_run)?;
+            workspace_commit(
+                &ws_meta,
+                &ws_config,
+                &selected_pkgs,
+                ws_config.pre_release_commit_message(),
+                dry_run,
+            )?;
         } else if !selected_pkgs.is_empty() {
             let selected_pkg = selected_pkgs
                 .first()
                 .expect("non-workspace can have at most 1 package");
-            pkg_commit(selected_pkg, dry_run)?;
+            pkg_commit(
+                selected_pkg,
+                selected_pkg.config.pre_release_commit_message(),
+                dry_run,
+            )?;
+        }
+        for pkg in &selected_pkgs {
+            super::hook::run_hook(&ws_meta, pkg, super::hook::HookStage::PostCommit, dry_run)?;
         }
 
         super::finish(failed, dry_run)
@@ -124,7 +138,12 @@ impl CommitStep {
     }
 }
 
-pub fn pkg_commit(pkg: &plan::PackageRelease, dry_run: bool) -> Result<(), CliError> {
+/// Commit everything for `pkg`, rendering `message` as a template
+pub fn pkg_commit(
+    pkg: &plan::PackageRelease,
+    message: &str,
+    dry_run: bool,
+) -> Result<(), CliError> {
     let cwd = &pkg.package_root;
     let crate_name = pkg.meta.name.as_str();
     let version = pkg.planned_version.as_ref().unwrap_or(&pkg.initial_version);
@@ -132,6 +151,8 @@ pub fn pkg_commit(pkg: &plan::PackageRelease, dry_run: bool) -> Result<(), CliEr
     let prev_metadata_var = pkg.initial_version.full_version.build.as_str();
     let version_var = version.bare_version_string.as_str();
     let metadata_var = version.full_version.build.as_str();
+    let branch = git::branch(cwd).ok().flatten();
+    let head_sha = git::head_id(cwd).ok();
     let template = Template {
         prev_version: Some(prev_version_var),
         prev_metadata: Some(prev_metadata_var),
@@ -139,9 +160,13 @@ pub fn pkg_commit(pkg: &plan::PackageRelease, dry_run: bool) -> Result<(), CliEr
         metadata: Some(metadata_var),
         crate_name: Some(crate_name),
         date: Some(NOW.as_str()),
+        branch: branch.as_deref(),
+        head_sha: head_sha.as_deref(),
+        prev_tag: pkg.prior_tag.as_deref(),
+        registry: Some(pkg.config.registry().unwrap_or(index::CRATES_IO)),
         ..Default::default()
     };
-    let commit_msg = template.render(pkg.config.pre_release_commit_message());
+    let commit_msg = template.render(message);
     let sign = pkg.config.sign_commit();
     if !git::commit_all(cwd, &commit_msg, sign, dry_run)? {
         // commit failed, abort release
@@ -151,14 +176,17 @@ pub fn pkg_commit(pkg: &plan::PackageRelease, dry_run: bool) -> Result<(), CliEr
     Ok(())
 }
 
+/// Commit everything for the workspace, rendering `message` as a template
 pub fn workspace_commit(
     ws_meta: &cargo_metadata::Metadata,
     ws_config: &config::Config,
     pkgs: &[plan::PackageRelease],
+    message: &str,
     dry_run: bool,
 ) -> Result<(), CliError> {
     let shared_version = super::find_shared_versions(pkgs)?;
 
+    let cwd = ws_meta.workspace_root.as_std_path();
     let shared_commit_msg = {
         let version_var = shared_version
             .as_ref()
@@ -166,20 +194,19 @@ pub fn workspace_commit(
         let metadata_var = shared_version
             .as_ref()
             .map(|v| v.full_version.build.as_str());
+        let branch = git::branch(cwd).ok().flatten();
+        let head_sha = git::head_id(cwd).ok();
         let template = Template {
             version: version_var,
             metadata: metadata_var,
             date: Some(NOW.as_str()),
+            branch: branch.as_deref(),
+            head_sha: head_sha.as_deref(),
             ..Default::default()
         };
-        template.render(ws_config.pre_release_commit_message())
+        template.render(message)
     };
-    if !git::commit_all(
-        ws_meta.workspace_root.as_std_path(),
-        &shared_commit_msg,
-        ws_config.sign_commit(),
-        dry_run,
-    )? {
+    if !git::commit_all(cwd, &shared_commit_msg, ws_config.sign_commit(), dry_run)? {
         // commit failed, abort release
         return Err(101.into());
     }
@@ -2,8 +2,16 @@ use std::io::Write;
 
 use crate::config::Config;
 use crate::config::ConfigArgs;
+use crate::config::ConfigLayer;
+use crate::config::ConfigOrigin;
+use crate::config::LOCK_FILE;
+use crate::config::env_var;
 use crate::config::load_package_config;
 use crate::config::load_workspace_config;
+use crate::config::package_config_layers;
+use crate::config::pin_extends;
+use crate::config::workspace_config_layers;
+use crate::error::CargoResult;
 use crate::error::CliError;
 
 /// Dump workspace configuration
@@ -13,6 +21,18 @@ pub struct ConfigStep {
     #[arg(short, long, default_value = "-")]
     output: std::path::PathBuf,
 
+    /// Package to show the configuration for
+    #[arg(short, long, value_name = "SPEC")]
+    package: Option<String>,
+
+    /// Annotate each setting with where it came from
+    #[arg(long)]
+    explain: bool,
+
+    /// Pin the current content of configs inherited through `extends`
+    #[arg(long, conflicts_with = "explain")]
+    pin: bool,
+
     #[command(flatten)]
     manifest: clap_cargo::Manifest,
 
@@ -30,24 +50,59 @@ impl ConfigStep {
             .features(cargo_metadata::CargoOpt::AllFeatures)
             .exec()?;
 
-        let release_config =
-            if let Some(root_id) = ws_meta.resolve.as_ref().and_then(|r| r.root.as_ref()) {
-                let pkg = ws_meta
-                    .packages
-                    .iter()
-                    .find(|p| p.id == *root_id)
-                    .expect("root should always be present");
-
-                let mut release_config = Config::from_defaults();
-                release_config.update(&load_package_config(&self.config, &ws_meta, pkg)?);
-                release_config
-            } else {
-                let mut release_config = Config::from_defaults();
-                release_config.update(&load_workspace_config(&self.config, &ws_meta)?);
-                release_config
-            };
+        if self.pin {
+            let mut layers = workspace_config_layers(&self.config, &ws_meta)?;
+            for pkg in ws_meta.workspace_packages() {
+                layers.extend(package_config_layers(&self.config, &ws_meta, pkg)?);
+            }
+            let count = pin_extends(ws_meta.workspace_root.as_std_path(), &layers)?;
+            let _ = crate::ops::shell::status(
+                "Pinned",
+                format!("{count} inherited config(s) in {LOCK_FILE}"),
+            );
+            return Ok(());
+        }
 
-        let output = toml::to_string_pretty(&release_config)?;
+        let pkg = if let Some(name) = self.package.as_deref() {
+            let pkg = ws_meta
+                .packages
+                .iter()
+                .find(|p| ws_meta.workspace_members.contains(&p.id) && p.name.as_str() == name)
+                .ok_or_else(|| anyhow::format_err!("package `{name}` not found in workspace"))?;
+            Some(pkg)
+        } else {
+            ws_meta
+                .resolve
+                .as_ref()
+                .and_then(|r| r.root.as_ref())
+                .map(|root_id| {
+                    ws_meta
+                        .packages
+                        .iter()
+                        .find(|p| p.id == *root_id)
+                        .expect("root should always be present")
+                })
+        };
+
+        let mut release_config = Config::from_defaults();
+        let layers = if let Some(pkg) = pkg {
+            release_config.update(&load_package_config(&self.config, &ws_meta, pkg)?);
+            package_config_layers(&self.config, &ws_meta, pkg)?
+        } else {
+            release_config.update(&load_workspace_config(&self.config, &ws_meta)?);
+            workspace_config_layers(&self.config, &ws_meta)?
+        };
+
+        let output = if self.explain {
+            let mut all_layers = vec![ConfigLayer {
+                origin: ConfigOrigin::Default,
+                config: Config::from_defaults(),
+            }];
+            all_layers.extend(layers);
+            explain(&release_config, &all_layers)?
+        } else {
+            toml::to_string_pretty(&release_config)?
+        };
 
         if self.output == std::path::Path::new("-") {
             std::io::stdout().write_all(output.as_bytes())?;
@@ -58,3 +113,84 @@ impl ConfigStep {
         Ok(())
     }
 }
+
+/// Render `config` with the origin of each setting, including values that were overridden
+fn explain(config: &Config, layers: &[ConfigLayer]) -> CargoResult<String> {
+    let mut layer_settings = Vec::new();
+    for layer in layers {
+        let mut settings = Vec::new();
+        flatten(
+            &mut settings,
+            Vec::new(),
+            toml::Table::try_from(&layer.config)?,
+        );
+        layer_settings.push(settings);
+    }
+
+    let mut settings = Vec::new();
+    flatten(&mut settings, Vec::new(), toml::Table::try_from(config)?);
+
+    let mut output = String::new();
+    for (key, value) in settings {
+        let key_ref = key.iter().map(|k| k.as_str()).collect::<Vec<_>>();
+        let mut sources = layers
+            .iter()
+            .zip(&layer_settings)
+            .filter_map(|(layer, settings)| {
+                let (_, value) = settings.iter().find(|(k, _)| *k == key)?;
+                Some((describe(&layer.origin, &key_ref), value))
+            })
+            .collect::<Vec<_>>();
+        let origin = sources
+            .pop()
+            .map(|(origin, _)| origin)
+            .unwrap_or_else(|| "default".to_owned());
+        output.push_str(&format!("{} = {value} # {origin}\n", key.join(".")));
+        for (origin, value) in sources.into_iter().rev() {
+            output.push_str(&format!("#   overrides {value} from {origin}\n"));
+        }
+    }
+    Ok(output)
+}
+
+fn flatten(
+    settings: &mut Vec<(Vec<String>, toml::Value)>,
+    prefix: Vec<String>,
+    table: toml::Table,
+) {
+    for (key, value) in table {
+        let mut key_path = prefix.clone();
+        key_path.push(key);
+        match value {
+            toml::Value::Table(table) => flatten(settings, key_path, table),
+            value => settings.push((key_path, value)),
+        }
+    }
+}
+
+fn describe(origin: &ConfigOrigin, key: &[&str]) -> String {
+    match origin {
+        ConfigOrigin::Default => "default".to_owned(),
+        ConfigOrigin::Env => format!("environment variable `{}`", env_var(key)),
+        ConfigOrigin::Cli => "CLI".to_owned(),
+        ConfigOrigin::File(source) => {
+            let path = source.path.display();
+            let location = if let Some(line) = source.line_of(key) {
+                format!("{path}:{line}")
+            } else {
+                path.to_string()
+            };
+            if let Some(profile) = source.profile.as_deref() {
+                format!("{location} (profile `{profile}`)")
+            } else if let Some(package) = source.package.as_deref() {
+                format!("{location} (package `{package}`)")
+            } else {
+                location
+            }
+        }
+        ConfigOrigin::Manifest(path) if key == ["registry"] => {
+            format!("{} (package.publish)", path.display())
+        }
+        ConfigOrigin::Manifest(path) => format!("{} (implied by package fields)", path.display()),
+    }
+}
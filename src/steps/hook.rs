@@ -93,13 +93,11 @@ impl HookStep {
                 && !explicitly_excluded
             {
                 let version = &pkg.initial_version;
-                if !crate::ops::cargo::is_published(
-                    &mut index,
-                    pkg.config.registry(),
-                    crate_name,
-                    &version.full_version_string,
-                    pkg.config.certs_source(),
-                ) {
+                if pkg
+                    .published_registries(&version.full_version_string, &mut index)?
+                    .len()
+                    < pkg.config.registries().len()
+                {
                     log::debug!(
                         "enabled {}, v{} is unpublished",
                         crate_name,
@@ -157,6 +155,7 @@ impl HookStep {
         for pkg in &selected_pkgs {
             hook(&ws_meta, pkg, dry_run)?;
         }
+        workspace_hook(&ws_meta, &ws_config, &selected_pkgs, dry_run)?;
 
         super::finish(failed, dry_run)
     }
@@ -172,12 +171,71 @@ impl HookStep {
     }
 }
 
+/// When a hook runs during a release
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum HookStage {
+    /// After replacements, before the release commit
+    #[default]
+    PreRelease,
+    /// After the release commit
+    PostCommit,
+    /// Before `cargo publish`
+    PrePublish,
+    /// After `cargo publish`
+    PostPublish,
+    /// After tagging
+    PostTag,
+    /// After pushing
+    PostPush,
+}
+
+impl HookStage {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::PreRelease => "pre-release",
+            Self::PostCommit => "post-commit",
+            Self::PrePublish => "pre-publish",
+            Self::PostPublish => "post-publish",
+            Self::PostTag => "post-tag",
+            Self::PostPush => "post-push",
+        }
+    }
+
+    fn command(self, config: &crate::config::Config) -> Option<&crate::config::Command> {
+        match self {
+            Self::PreRelease => config.pre_release_hook(),
+            Self::PostCommit => config.post_commit_hook(),
+            Self::PrePublish => config.pre_publish_hook(),
+            Self::PostPublish => config.post_publish_hook(),
+            Self::PostTag => config.post_tag_hook(),
+            Self::PostPush => config.post_push_hook(),
+        }
+    }
+}
+
+impl std::fmt::Display for HookStage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.as_str().fmt(f)
+    }
+}
+
 pub fn hook(
     ws_meta: &cargo_metadata::Metadata,
     pkg: &plan::PackageRelease,
     dry_run: bool,
 ) -> Result<(), CliError> {
-    if let Some(pre_rel_hook) = pkg.config.pre_release_hook() {
+    run_hook(ws_meta, pkg, HookStage::PreRelease, dry_run)
+}
+
+/// Run the hook for `stage`, if `pkg` has one
+pub fn run_hook(
+    ws_meta: &cargo_metadata::Metadata,
+    pkg: &plan::PackageRelease,
+    stage: HookStage,
+    dry_run: bool,
+) -> Result<(), CliError> {
+    if let Some(hook) = stage.command(&pkg.config) {
         let cwd = &pkg.package_root;
         let crate_name = pkg.meta.name.as_str();
         let version = pkg.planned_version.as_ref().unwrap_or(&pkg.initial_version);
@@ -185,6 +243,8 @@ pub fn hook(
         let prev_metadata_var = pkg.initial_version.full_version.build.as_str();
         let version_var = version.bare_version_string.as_str();
         let metadata_var = version.full_version.build.as_str();
+        let branch = git::branch(cwd).ok().flatten();
+        let head_sha = git::head_id(cwd).ok();
         let template = Template {
             prev_version: Some(prev_version_var),
             prev_metadata: Some(prev_metadata_var),
@@ -192,16 +252,24 @@ pub fn hook(
             metadata: Some(metadata_var),
             crate_name: Some(crate_name),
             date: Some(NOW.as_str()),
+            branch: branch.as_deref(),
+            head_sha: head_sha.as_deref(),
+            prev_tag: pkg.prior_tag.as_deref(),
+            registry: Some(
+                pkg.config
+                    .registry()
+                    .unwrap_or(crate::ops::index::CRATES_IO),
+            ),
             tag_name: pkg.planned_tag.as_deref(),
             ..Default::default()
         };
-        let pre_rel_hook = pre_rel_hook
+        let hook = hook
             .args()
             .into_iter()
             .map(|arg| template.render(arg))
             .collect::<Vec<_>>();
-        log::debug!("calling pre-release hook: {pre_rel_hook:?}");
-        let envs = maplit::btreemap! {
+        log::debug!("calling {stage} hook: {hook:?}");
+        let mut envs = maplit::btreemap! {
             OsStr::new("PREV_VERSION") => prev_version_var.as_ref(),
             OsStr::new("PREV_METADATA") => prev_metadata_var.as_ref(),
             OsStr::new("NEW_VERSION") => version_var.as_ref(),
@@ -210,12 +278,37 @@ pub fn hook(
             OsStr::new("CRATE_NAME") => OsStr::new(crate_name),
             OsStr::new("WORKSPACE_ROOT") => ws_meta.workspace_root.as_os_str(),
             OsStr::new("CRATE_ROOT") => pkg.manifest_path.parent().unwrap_or_else(|| Path::new(".")).as_os_str(),
+            OsStr::new("HOOK_STAGE") => OsStr::new(stage.as_str()),
         };
+        let commit_id;
+        match stage {
+            HookStage::PreRelease => {}
+            HookStage::PostCommit => {
+                commit_id = git::head_id(cwd)?;
+                envs.insert(OsStr::new("COMMIT_ID"), OsStr::new(commit_id.as_str()));
+            }
+            HookStage::PrePublish | HookStage::PostPublish => {
+                if let Some(registry) = pkg.config.registry() {
+                    envs.insert(OsStr::new("REGISTRY"), OsStr::new(registry));
+                }
+            }
+            HookStage::PostTag | HookStage::PostPush => {
+                if let Some(tag_name) = pkg.planned_tag.as_deref() {
+                    envs.insert(OsStr::new("TAG_NAME"), OsStr::new(tag_name));
+                }
+                if stage == HookStage::PostPush {
+                    envs.insert(
+                        OsStr::new("PUSH_REMOTE"),
+                        OsStr::new(pkg.config.push_remote()),
+                    );
+                }
+            }
+        }
         // we use dry_run environmental variable to run the script
         // so here we set dry_run=false and always execute the command.
-        if !cmd::call_with_env(pre_rel_hook, envs, cwd, false)? {
+        if !cmd::call_with_env(hook, envs, cwd, false)? {
             let _ = crate::ops::shell::error(format!(
-                "release of {crate_name} aborted by non-zero return of prerelease hook."
+                "release of {crate_name} aborted by non-zero return of {stage} hook."
             ));
             return Err(101.into());
         }
@@ -223,3 +316,72 @@ pub fn hook(
 
     Ok(())
 }
+
+/// A package being released, as described to the `workspace-pre-release-hook`
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "kebab-case")]
+struct WorkspaceHookPackage<'a> {
+    name: &'a str,
+    prev_version: &'a str,
+    prev_metadata: &'a str,
+    new_version: &'a str,
+    new_metadata: &'a str,
+    crate_root: &'a Path,
+}
+
+/// Run the `workspace-pre-release-hook` once for all of `pkgs`
+pub fn workspace_hook(
+    ws_meta: &cargo_metadata::Metadata,
+    ws_config: &crate::config::Config,
+    pkgs: &[plan::PackageRelease],
+    dry_run: bool,
+) -> Result<(), CliError> {
+    if let Some(hook) = ws_config.workspace_pre_release_hook() {
+        let cwd = ws_meta.workspace_root.as_std_path();
+        let packages = pkgs
+            .iter()
+            .map(|pkg| {
+                let version = pkg.planned_version.as_ref().unwrap_or(&pkg.initial_version);
+                WorkspaceHookPackage {
+                    name: pkg.meta.name.as_str(),
+                    prev_version: pkg.initial_version.bare_version_string.as_str(),
+                    prev_metadata: pkg.initial_version.full_version.build.as_str(),
+                    new_version: version.bare_version_string.as_str(),
+                    new_metadata: version.full_version.build.as_str(),
+                    crate_root: pkg.package_root.as_path(),
+                }
+            })
+            .collect::<Vec<_>>();
+        let packages = serde_json::to_string(&packages)?;
+
+        let branch = git::branch(cwd).ok().flatten();
+        let head_sha = git::head_id(cwd).ok();
+        let template = Template {
+            date: Some(NOW.as_str()),
+            branch: branch.as_deref(),
+            head_sha: head_sha.as_deref(),
+            ..Default::default()
+        };
+        let hook = hook
+            .args()
+            .into_iter()
+            .map(|arg| template.render(arg))
+            .collect::<Vec<_>>();
+        log::debug!("calling workspace pre-release hook: {hook:?}");
+        let envs = maplit::btreemap! {
+            OsStr::new("DRY_RUN") => OsStr::new(if dry_run { "true" } else { "false" }),
+            OsStr::new("WORKSPACE_ROOT") => ws_meta.workspace_root.as_os_str(),
+            OsStr::new("RELEASE_PACKAGES") => OsStr::new(packages.as_str()),
+        };
+        // we use dry_run environmental variable to run the script
+        // so here we set dry_run=false and always execute the command.
+        if !cmd::call_with_input(hook, envs, packages.as_bytes(), cwd, false)? {
+            let _ = crate::ops::shell::error(
+                "release aborted by non-zero return of workspace pre-release hook.",
+            );
+            return Err(101.into());
+        }
+    }
+
+    Ok(())
+}
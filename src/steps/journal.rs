@@ -0,0 +1,166 @@
+use std::path::Path;
+use std::path::PathBuf;
+
+use anyhow::Context as _;
+
+use crate::error::CargoResult;
+use crate::error::CliError;
+use crate::ops::git;
+use crate::steps::plan;
+
+/// Progress of a release, so an interrupted one can be picked up with `--resume`
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+#[serde(rename_all = "kebab-case")]
+pub struct Journal {
+    pub plan: plan::FrozenPlan,
+    pub completed: Vec<Action>,
+    #[serde(skip)]
+    pub path: PathBuf,
+}
+
+impl Journal {
+    /// Where the journal for the repo containing `dir` is kept
+    ///
+    /// This lives in the git directory so it survives `cargo clean` and doesn't dirty the
+    /// worktree.
+    pub fn path(dir: &Path) -> CargoResult<PathBuf> {
+        let git_dir = git::git_dir(dir)?;
+        Ok(git_dir.join("cargo-release").join("journal.json"))
+    }
+
+    pub fn new(path: PathBuf, plan: plan::FrozenPlan) -> Self {
+        Self {
+            plan,
+            completed: Vec::new(),
+            path,
+        }
+    }
+
+    pub fn load(path: &Path) -> CargoResult<Option<Self>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read `{}`", path.display()))?;
+        let mut journal: Self = serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse `{}`", path.display()))?;
+        journal.path = path.to_owned();
+        Ok(Some(journal))
+    }
+
+    pub fn save(&self, dry_run: bool) -> CargoResult<()> {
+        if dry_run {
+            return Ok(());
+        }
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut content = serde_json::to_string_pretty(self)?;
+        content.push('\n');
+        std::fs::write(&self.path, content)
+            .with_context(|| format!("Failed to write `{}`", self.path.display()))?;
+        Ok(())
+    }
+
+    /// The release is complete, there is nothing left to resume
+    pub fn finish(&self, dry_run: bool) -> CargoResult<()> {
+        if !dry_run && self.path.exists() {
+            std::fs::remove_file(&self.path)
+                .with_context(|| format!("Failed to remove `{}`", self.path.display()))?;
+        }
+        Ok(())
+    }
+
+    pub fn is_done(&self, action: &Action) -> bool {
+        self.completed.contains(action)
+    }
+
+    pub fn record(&mut self, action: Action, dry_run: bool) -> CargoResult<()> {
+        if !self.is_done(&action) {
+            self.completed.push(action);
+        }
+        self.save(dry_run)
+    }
+
+    /// Run `action` unless a prior run already completed it
+    pub fn run(
+        &mut self,
+        action: Action,
+        dry_run: bool,
+        f: impl FnOnce() -> Result<(), CliError>,
+    ) -> Result<(), CliError> {
+        if self.is_done(&action) {
+            let _ = crate::ops::shell::status("Skipping", format!("{action}, already done"));
+            return Ok(());
+        }
+        f()?;
+        self.record(action, dry_run)?;
+        Ok(())
+    }
+}
+
+/// A side effect of a release
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "action")]
+#[serde(rename_all = "kebab-case")]
+pub enum Action {
+    /// Versions were bumped, either for one package or for the whole workspace
+    Version {
+        package: Option<String>,
+    },
+    Changelog {
+        package: String,
+    },
+    Replace {
+        package: String,
+    },
+    Hook {
+        package: String,
+        #[serde(default)]
+        stage: super::hook::HookStage,
+    },
+    /// The `workspace-pre-release-hook` ran for the whole workspace
+    WorkspaceHook,
+    /// The release was committed, either for one package or for the whole workspace
+    Commit {
+        package: Option<String>,
+    },
+    Publish {
+        package: String,
+    },
+    Tag {
+        package: String,
+    },
+    /// Moved on to the development version, either for one package or for the whole workspace
+    PostRelease {
+        package: Option<String>,
+    },
+    Push,
+}
+
+impl std::fmt::Display for Action {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Version { package: None } => "version bump".fmt(f),
+            Self::Version {
+                package: Some(package),
+            } => write!(f, "version bump of {package}"),
+            Self::Changelog { package } => write!(f, "changelog for {package}"),
+            Self::Replace { package } => write!(f, "replacements for {package}"),
+            Self::Hook { package, stage } => write!(f, "{stage} hook for {package}"),
+            Self::WorkspaceHook => "workspace pre-release hook".fmt(f),
+            Self::Commit { package: None } => "release commit".fmt(f),
+            Self::Commit {
+                package: Some(package),
+            } => write!(f, "release commit for {package}"),
+            Self::Publish { package } => write!(f, "publish of {package}"),
+            Self::Tag { package } => write!(f, "tag for {package}"),
+            Self::PostRelease { package: None } => "development version bump".fmt(f),
+            Self::PostRelease {
+                package: Some(package),
+            } => write!(f, "development version bump of {package}"),
+            Self::Push => "push".fmt(f),
+        }
+    }
+}
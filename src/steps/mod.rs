@@ -1,16 +1,21 @@
 use std::str::FromStr;
 
+pub mod changelog;
 pub mod changes;
 pub mod commit;
 pub mod config;
 pub mod hook;
+pub mod journal;
 pub mod owner;
 pub mod plan;
+pub mod post_release;
 pub mod publish;
 pub mod push;
 pub mod release;
 pub mod replace;
+pub mod status;
 pub mod tag;
+pub mod undo;
 pub mod version;
 
 use crate::error::CargoResult;
@@ -237,7 +242,7 @@ pub fn verify_rate_limit(
     let mut existing = 0;
     for pkg in pkgs {
         // Note: these rate limits are only known for default registry
-        if pkg.config.registry().is_none() && pkg.config.publish() {
+        if pkg.config.registries().contains(&None) && pkg.config.publish() {
             let crate_name = pkg.meta.name.as_str();
             if index.has_krate(None, crate_name, pkg.config.certs_source())? {
                 existing += 1;
@@ -247,6 +252,25 @@ pub fn verify_rate_limit(
         }
     }
 
+    if rate_limit.throttle() {
+        let mut limiter = publish::RateLimiter::new(rate_limit);
+        let mut finish = std::time::Instant::now();
+        for pkg in pkgs {
+            if pkg.config.publish() {
+                finish = finish.max(limiter.reserve(pkg, index)?);
+            }
+        }
+        let wait = finish.saturating_duration_since(std::time::Instant::now());
+        if !wait.is_zero() {
+            let _ = crate::ops::shell::note(format!(
+                "publishing {new} new and {existing} existing crates within the rate limits of crates.io will take until about {} ({} minutes)",
+                publish::utc_time(finish),
+                wait.as_secs().div_ceil(60)
+            ));
+        }
+        return Ok(success);
+    }
+
     if rate_limit.new_packages() < new {
         // "The rate limit for creating new crates is 1 crate every 10 minutes, with a burst of 5 crates."
         success = false;
@@ -508,9 +532,13 @@ pub fn finish(failed: bool, dry_run: bool) -> Result<(), crate::error::CliError>
 pub enum TargetVersion {
     Relative(BumpLevel),
     Absolute(semver::Version),
+    /// Infer the [`BumpLevel`] from the conventional commits since the last tag
+    Auto,
 }
 
 impl TargetVersion {
+    const AUTO: &'static str = "auto";
+
     pub fn bump(
         &self,
         current: &semver::Version,
@@ -544,6 +572,9 @@ impl TargetVersion {
                     Ok(None)
                 }
             }
+            Self::Auto => {
+                anyhow::bail!("`{self}` must be resolved to a level for each package")
+            }
         }
     }
 }
@@ -563,6 +594,9 @@ impl std::fmt::Display for TargetVersion {
             Self::Absolute(version) => {
                 write!(f, "{version}")
             }
+            Self::Auto => {
+                write!(f, "{}", Self::AUTO)
+            }
         }
     }
 }
@@ -573,6 +607,8 @@ impl FromStr for TargetVersion {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         if let Ok(bump_level) = BumpLevel::from_str(s) {
             Ok(Self::Relative(bump_level))
+        } else if s == Self::AUTO {
+            Ok(Self::Auto)
         } else {
             Ok(Self::Absolute(
                 semver::Version::parse(s).map_err(|e| e.to_string())?,
@@ -610,7 +646,11 @@ impl clap::builder::TypedValueParser for TargetVersionParser {
     ) -> Option<Box<dyn Iterator<Item = clap::builder::PossibleValue> + '_>> {
         let inner_parser = clap::builder::EnumValueParser::<BumpLevel>::new();
         inner_parser.possible_values().map(|ps| {
-            let ps = ps.collect::<Vec<_>>();
+            let mut ps = ps.collect::<Vec<_>>();
+            ps.push(
+                clap::builder::PossibleValue::new(TargetVersion::AUTO)
+                    .help("Infer the level from conventional commits since the last tag"),
+            );
             let ps: Box<dyn Iterator<Item = clap::builder::PossibleValue> + '_> =
                 Box::new(ps.into_iter());
             ps
@@ -618,7 +658,7 @@ impl clap::builder::TypedValueParser for TargetVersionParser {
     }
 }
 
-#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
 #[value(rename_all = "kebab-case")]
 pub enum BumpLevel {
     /// Increase the major version (x.0.0)
@@ -715,21 +755,45 @@ pub fn verify_dependencies(
     level: log::Level,
 ) -> Result<bool, crate::error::CliError> {
     let mut success = true;
+    // Dependencies record their registry by index URL
+    let mut registry_names = std::collections::HashMap::new();
+    for url in selected_pkgs
+        .iter()
+        .flat_map(|pkg| &pkg.meta.dependencies)
+        .filter_map(|dependency| dependency.registry.as_deref())
+    {
+        if !registry_names.contains_key(url) {
+            let name = crate::ops::index::registry_name(url)?;
+            if name.is_none() {
+                log::debug!("No registry is configured with index `{url}`");
+            }
+            registry_names.insert(url, name);
+        }
+    }
 
     for pkg in selected_pkgs {
-        if !pkg.config.publish() || pkg.config.registry().is_some() {
+        if !pkg.config.publish() {
             continue;
         }
         let version = pkg.planned_version.as_ref().unwrap_or(&pkg.initial_version);
+        let registries = pkg.config.registries();
         let mut checked = std::collections::HashSet::new();
         for dependency in &pkg.meta.dependencies {
-            if dependency.registry.is_some()
-                || (dependency.req == semver::VersionReq::STAR
-                    && !dependency
-                        .source
-                        .as_ref()
-                        .is_some_and(cargo_metadata::Source::is_crates_io))
-                || !checked.insert((dependency.name.as_str(), &dependency.req))
+            let registry = match dependency.registry.as_deref() {
+                Some(url) => {
+                    let Some(registry) = registry_names[url].as_deref() else {
+                        continue;
+                    };
+                    Some(registry)
+                }
+                None => None,
+            };
+            if (dependency.req == semver::VersionReq::STAR
+                && !dependency
+                    .source
+                    .as_ref()
+                    .is_some_and(cargo_metadata::Source::is_crates_io))
+                || !checked.insert((dependency.name.as_str(), &dependency.req, registry))
             {
                 continue;
             }
@@ -740,18 +804,18 @@ pub fn verify_dependencies(
                     .as_ref()
                     .unwrap_or(&candidate.initial_version);
                 candidate.config.publish()
-                    && candidate.config.registry() == pkg.config.registry()
+                    && candidate.config.registries().contains(&registry)
                     && candidate.meta.name.as_str() == dependency.name.as_str()
                     && dependency.req.matches(&candidate_version.full_version)
             });
             if publishing
                 || crate::ops::cargo::is_published_req(
                     index,
-                    pkg.config.registry(),
+                    registry,
                     &dependency.name,
                     &dependency.req,
                     pkg.config.certs_source(),
-                )
+                )?
             {
                 continue;
             }
@@ -760,7 +824,7 @@ pub fn verify_dependencies(
                 .iter()
                 .chain(excluded_pkgs)
                 .find(|candidate| candidate.meta.name.as_str() == dependency.name.as_str());
-            let message = if let Some(workspace_dependency) = workspace_dependency {
+            let mut message = if let Some(workspace_dependency) = workspace_dependency {
                 let dependency_version = workspace_dependency
                     .planned_version
                     .as_ref()
@@ -778,6 +842,9 @@ pub fn verify_dependencies(
                     pkg.meta.name, version.full_version_string, dependency.name, dependency.req
                 )
             };
+            if 1 < registries.len() {
+                message.push_str(&format!(" on {}", registry.unwrap_or("crates.io")));
+            }
             let _ = crate::ops::shell::log(level, message);
             success = false;
         }
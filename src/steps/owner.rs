@@ -151,12 +151,9 @@ pub fn ensure_owners(pkgs: &[plan::PackageRelease], dry_run: bool) -> Result<(),
         }
 
         let crate_name = pkg.meta.name.as_str();
-        crate::ops::cargo::ensure_owners(
-            crate_name,
-            pkg.config.owners(),
-            pkg.config.registry(),
-            dry_run,
-        )?;
+        for registry in pkg.config.registries() {
+            crate::ops::cargo::ensure_owners(crate_name, pkg.config.owners(), registry, dry_run)?;
+        }
     }
 
     Ok(())
@@ -1,8 +1,11 @@
 use std::path::Path;
 use std::path::PathBuf;
 
+use anyhow::Context as _;
+
 use crate::config;
 use crate::error::CargoResult;
+use crate::error::CliError;
 use crate::ops::cargo;
 use crate::ops::git;
 use crate::ops::replace::Template;
@@ -73,7 +76,7 @@ pub fn plan(
     Ok(pkgs)
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct PackageRelease {
     pub meta: cargo_metadata::Package,
     pub manifest_path: PathBuf,
@@ -91,6 +94,8 @@ pub struct PackageRelease {
 
     pub planned_version: Option<Version>,
     pub planned_tag: Option<String>,
+    /// Development version to move to after the release
+    pub post_release_version: Option<Version>,
 
     pub ensure_owners: bool,
 }
@@ -177,6 +182,7 @@ impl PackageRelease {
 
         let planned_version = None;
         let planned_tag = None;
+        let post_release_version = None;
         let ensure_owners = config.publish() && !config.owners().is_empty();
 
         let pkg = Self {
@@ -196,6 +202,7 @@ impl PackageRelease {
 
             planned_version,
             planned_tag,
+            post_release_version,
             ensure_owners,
         };
         Ok(pkg)
@@ -205,11 +212,57 @@ impl PackageRelease {
         self.prior_tag = Some(prior_tag);
     }
 
+    /// This release, only publishing to `registries`
+    pub fn for_registries(&self, registries: &[Option<&str>]) -> Self {
+        let mut pkg = self.clone();
+        pkg.config.registry = Some(config::Registries::Names(
+            registries
+                .iter()
+                .map(|r| r.unwrap_or(config::Registries::CRATES_IO).to_owned())
+                .collect(),
+        ));
+        pkg
+    }
+
+    /// The registries being published to that already have `version`
+    pub fn published_registries(
+        &self,
+        version: &str,
+        index: &mut crate::ops::index::CratesIoIndex,
+    ) -> Result<Vec<Option<&str>>, CliError> {
+        let mut published = Vec::new();
+        for registry in self.config.registries() {
+            if cargo::is_published(
+                index,
+                registry,
+                self.meta.name.as_str(),
+                version,
+                self.config.certs_source(),
+            )? {
+                published.push(registry);
+            }
+        }
+        Ok(published)
+    }
+
     pub fn bump<'s>(
         &'s mut self,
+        ws_meta: &cargo_metadata::Metadata,
         level_or_version: &super::TargetVersion,
         mut metadata: Option<&'s str>,
     ) -> CargoResult<()> {
+        let auto_level;
+        let level_or_version = if let super::TargetVersion::Auto = level_or_version {
+            let Some(level) = self.auto_level(ws_meta)? else {
+                self.planned_version = None;
+                return Ok(());
+            };
+            auto_level = super::TargetVersion::Relative(level);
+            &auto_level
+        } else {
+            level_or_version
+        };
+
         match self.config.metadata() {
             config::MetadataPolicy::Optional => {}
             config::MetadataPolicy::Required => {
@@ -238,6 +291,60 @@ impl PackageRelease {
         Ok(())
     }
 
+    /// Infer the bump level from the conventional commits since [`PackageRelease::prior_tag`]
+    ///
+    /// Packages without relevant changes are excluded from the release.
+    fn auto_level(
+        &mut self,
+        ws_meta: &cargo_metadata::Metadata,
+    ) -> CargoResult<Option<super::BumpLevel>> {
+        let crate_name = self.meta.name.as_str();
+        let Some(prior_tag_name) = self.prior_tag.as_deref() else {
+            log::debug!(
+                "cannot infer the version bump for {crate_name} because no tag was found, releasing as-is"
+            );
+            return Ok(None);
+        };
+
+        let workspace_root = ws_meta.workspace_root.as_std_path();
+        let commits = super::changes::package_commits(workspace_root, self, prior_tag_name)?;
+        let max_status = super::changes::max_status(&commits)
+            .filter(|s| *s != super::changes::CommitStatus::Ignore);
+        let Some(max_status) = max_status else {
+            log::debug!("no relevant changes for {crate_name} since {prior_tag_name}, skipping");
+            self.config.release = Some(false);
+            return Ok(None);
+        };
+        if self.initial_version.is_prerelease() {
+            let _ = crate::ops::shell::warn(format!(
+                "cannot infer the version bump for pre-release {} {}, skipping",
+                crate_name, self.initial_version.full_version_string
+            ));
+            self.config.release = Some(false);
+            return Ok(None);
+        }
+
+        let initial_tag = render_tag(
+            self.config.tag_name(),
+            self.config.tag_prefix(self.is_root),
+            crate_name,
+            &self.initial_version,
+            &self.initial_version,
+        );
+        let bumped = !git::tag_exists(&self.package_root, &initial_tag)?;
+        let level =
+            super::changes::suggested_bump(&self.initial_version.full_version, max_status, bumped);
+        if level.is_none() {
+            log::debug!(
+                "{} {} already accounts for changes since {}",
+                crate_name,
+                self.initial_version.full_version_string,
+                prior_tag_name
+            );
+        }
+        Ok(level)
+    }
+
     pub fn plan(&mut self) -> CargoResult<()> {
         if !self.config.release() {
             return Ok(());
@@ -262,12 +369,56 @@ impl PackageRelease {
             None
         };
 
+        let post_release_version = self
+            .config
+            .post_release_version()
+            .map(|spec| render_post_release_version(spec, base))
+            .transpose()
+            .with_context(|| format!("invalid `post-release-version` for {}", self.meta.name))?;
+
         self.planned_tag = tag;
+        self.post_release_version = post_release_version;
 
         Ok(())
     }
 }
 
+/// Resolve `post-release-version`, either a bump level or a version template, after `released`
+fn render_post_release_version(spec: &str, released: &Version) -> CargoResult<Version> {
+    let next = if let Ok(level) = spec.parse::<super::BumpLevel>() {
+        let mut next = released.bare_version.clone();
+        level.bump_version(&mut next, None)?;
+        next
+    } else {
+        let mut next_major = released.bare_version.clone();
+        next_major.increment_major();
+        let next_major = next_major.to_string();
+        let mut next_minor = released.bare_version.clone();
+        next_minor.increment_minor();
+        let next_minor = next_minor.to_string();
+        let mut next_patch = released.bare_version.clone();
+        next_patch.increment_patch();
+        let next_patch = next_patch.to_string();
+        let template = Template {
+            version: Some(released.bare_version_string.as_str()),
+            next_major: Some(next_major.as_str()),
+            next_minor: Some(next_minor.as_str()),
+            next_patch: Some(next_patch.as_str()),
+            ..Default::default()
+        };
+        let rendered = template.render(spec);
+        semver::Version::parse(&rendered)
+            .with_context(|| format!("`{rendered}` is not a valid version"))?
+    };
+    if next <= released.bare_version {
+        anyhow::bail!(
+            "{next} must be after the released version {}",
+            released.bare_version_string
+        );
+    }
+    Ok(Version::from(next))
+}
+
 fn render_tag(
     tag_name: &str,
     tag_prefix: &str,
@@ -328,7 +479,7 @@ fn find_dependents<'w>(
     })
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct Dependency {
     pub pkg: cargo_metadata::Package,
     pub req: semver::VersionReq,
@@ -362,3 +513,298 @@ impl From<semver::Version> for Version {
         }
     }
 }
+
+/// A resolved release, written by `--plan-out` and carried out by `--plan`
+///
+/// Only the packages selected for release are recorded.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+#[serde(rename_all = "kebab-case")]
+pub struct FrozenPlan {
+    /// Commit the plan was resolved against
+    pub head: String,
+    /// Blob id of the workspace manifest
+    pub workspace_manifest: String,
+    pub packages: Vec<FrozenPackage>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+#[serde(rename_all = "kebab-case")]
+pub struct FrozenPackage {
+    pub name: String,
+    /// Relative to the workspace root
+    pub manifest_path: PathBuf,
+    /// Blob id of the package manifest
+    pub manifest: String,
+    pub initial_version: String,
+    pub prior_tag: Option<String>,
+    pub planned_version: Option<String>,
+    pub planned_tag: Option<String>,
+    pub post_release_version: Option<String>,
+    /// Registry state when publishing
+    pub index: Option<IndexState>,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+#[serde(rename_all = "kebab-case")]
+pub struct IndexState {
+    /// Whether every registry being published to has the crate
+    pub crate_exists: bool,
+    /// Whether any registry being published to has the version
+    pub version_published: bool,
+}
+
+impl FrozenPlan {
+    pub fn new<'p>(
+        ws_meta: &cargo_metadata::Metadata,
+        pkgs: impl IntoIterator<Item = &'p PackageRelease>,
+        index: &mut crate::ops::index::CratesIoIndex,
+    ) -> Result<Self, CliError> {
+        let workspace_root = ws_meta.workspace_root.as_std_path();
+        let head = git::head_id(workspace_root)?;
+        let workspace_manifest = git::blob_id(&workspace_root.join("Cargo.toml"))?;
+        let packages = pkgs
+            .into_iter()
+            .filter(|pkg| pkg.config.release())
+            .map(|pkg| {
+                let version = pkg.planned_version.as_ref().unwrap_or(&pkg.initial_version);
+                let index = if pkg.config.publish() {
+                    Some(IndexState::new(pkg, version, index)?)
+                } else {
+                    None
+                };
+                Ok(FrozenPackage {
+                    name: pkg.meta.name.as_str().to_owned(),
+                    manifest_path: pkg
+                        .manifest_path
+                        .strip_prefix(workspace_root)
+                        .unwrap_or(&pkg.manifest_path)
+                        .to_owned(),
+                    manifest: git::blob_id(&pkg.manifest_path)?,
+                    initial_version: pkg.initial_version.full_version_string.clone(),
+                    prior_tag: pkg.prior_tag.clone(),
+                    planned_version: pkg
+                        .planned_version
+                        .as_ref()
+                        .map(|v| v.full_version_string.clone()),
+                    planned_tag: pkg.planned_tag.clone(),
+                    post_release_version: pkg
+                        .post_release_version
+                        .as_ref()
+                        .map(|v| v.full_version_string.clone()),
+                    index,
+                })
+            })
+            .collect::<Result<_, CliError>>()?;
+        Ok(Self {
+            head,
+            workspace_manifest,
+            packages,
+        })
+    }
+
+    pub fn load(path: &Path) -> CargoResult<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read `{}`", path.display()))?;
+        let plan = serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse `{}`", path.display()))?;
+        Ok(plan)
+    }
+
+    pub fn save(&self, path: &Path) -> CargoResult<()> {
+        let mut content = serde_json::to_string_pretty(self)?;
+        content.push('\n');
+        std::fs::write(path, content)
+            .with_context(|| format!("Failed to write `{}`", path.display()))?;
+        Ok(())
+    }
+
+    /// Report how the workspace and registry differ from when the plan was written
+    ///
+    /// Only the packages in the plan are checked as the plan, not the command-line, decides the
+    /// selection.
+    pub fn drift(
+        &self,
+        ws_meta: &cargo_metadata::Metadata,
+        pkgs: &indexmap::IndexMap<cargo_metadata::PackageId, PackageRelease>,
+        index: &mut crate::ops::index::CratesIoIndex,
+    ) -> Result<Vec<String>, CliError> {
+        let mut drift = Vec::new();
+
+        let workspace_root = ws_meta.workspace_root.as_std_path();
+        let head = git::head_id(workspace_root)?;
+        if head != self.head {
+            drift.push(format!("HEAD moved from {} to {}", self.head, head));
+        }
+        let workspace_manifest = git::blob_id(&workspace_root.join("Cargo.toml"))?;
+        if workspace_manifest != self.workspace_manifest {
+            drift.push("workspace manifest changed".to_owned());
+        }
+
+        for frozen in &self.packages {
+            let Some(pkg) = pkgs
+                .values()
+                .find(|pkg| pkg.meta.name.as_str() == frozen.name)
+            else {
+                drift.push(format!("{} is no longer in the workspace", frozen.name));
+                continue;
+            };
+            let crate_name = frozen.name.as_str();
+
+            if !pkg.config.release() {
+                drift.push(format!("{crate_name} is no longer released"));
+            }
+            if git::blob_id(&pkg.manifest_path)? != frozen.manifest {
+                drift.push(format!("{crate_name}'s manifest changed"));
+            }
+            if pkg.initial_version.full_version_string != frozen.initial_version {
+                drift.push(format!(
+                    "{} version changed from {} to {}",
+                    crate_name, frozen.initial_version, pkg.initial_version.full_version_string
+                ));
+            }
+            if let Some(frozen_index) = frozen.index {
+                let version = frozen
+                    .planned_version()?
+                    .unwrap_or_else(|| pkg.initial_version.clone());
+                let current_index = IndexState::new(pkg, &version, index)?;
+                if current_index != frozen_index {
+                    let registries = if current_index.version_published {
+                        pkg.published_registries(&version.full_version_string, index)?
+                    } else {
+                        pkg.config.registries()
+                    };
+                    let registries = registries
+                        .into_iter()
+                        .map(|r| r.unwrap_or("crates.io"))
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    if current_index.version_published {
+                        drift.push(format!(
+                            "{} {} has been published to {}",
+                            crate_name, version.full_version_string, registries
+                        ));
+                    } else {
+                        drift.push(format!("{crate_name} changed on {registries}"));
+                    }
+                }
+            }
+        }
+
+        Ok(drift)
+    }
+
+    /// Replace the selection, versions, and tags with those from the plan
+    pub fn apply(
+        &self,
+        pkgs: &mut indexmap::IndexMap<cargo_metadata::PackageId, PackageRelease>,
+    ) -> CargoResult<()> {
+        for pkg in pkgs.values_mut() {
+            let crate_name = pkg.meta.name.as_str();
+            let Some(frozen) = self.packages.iter().find(|p| p.name == crate_name) else {
+                pkg.config.release = Some(false);
+                pkg.planned_version = None;
+                pkg.planned_tag = None;
+                pkg.post_release_version = None;
+                continue;
+            };
+            pkg.config.release = Some(true);
+            pkg.initial_version = semver::Version::parse(&frozen.initial_version)
+                .map(Version::from)
+                .with_context(|| format!("Invalid initial version for {crate_name}"))?;
+            pkg.prior_tag = frozen.prior_tag.clone();
+            pkg.planned_version = frozen.planned_version()?;
+            pkg.planned_tag = frozen.planned_tag.clone();
+            pkg.post_release_version = frozen.post_release_version()?;
+        }
+        Ok(())
+    }
+}
+
+impl FrozenPackage {
+    fn planned_version(&self) -> CargoResult<Option<Version>> {
+        self.planned_version
+            .as_deref()
+            .map(|v| {
+                semver::Version::parse(v)
+                    .map(Version::from)
+                    .with_context(|| format!("Invalid planned version for {}", self.name))
+            })
+            .transpose()
+    }
+
+    fn post_release_version(&self) -> CargoResult<Option<Version>> {
+        self.post_release_version
+            .as_deref()
+            .map(|v| {
+                semver::Version::parse(v)
+                    .map(Version::from)
+                    .with_context(|| format!("Invalid post-release version for {}", self.name))
+            })
+            .transpose()
+    }
+}
+
+impl IndexState {
+    fn new(
+        pkg: &PackageRelease,
+        version: &Version,
+        index: &mut crate::ops::index::CratesIoIndex,
+    ) -> Result<Self, CliError> {
+        let crate_name = pkg.meta.name.as_str();
+        let mut crate_exists = true;
+        for registry in pkg.config.registries() {
+            crate_exists &= index.has_krate(registry, crate_name, pkg.config.certs_source())?;
+        }
+        let version_published = !pkg
+            .published_registries(&version.full_version_string, index)?
+            .is_empty();
+        Ok(Self {
+            crate_exists,
+            version_published,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    mod render_post_release_version {
+        use super::*;
+
+        fn released(version: &str) -> Version {
+            Version::from(semver::Version::parse(version).unwrap())
+        }
+
+        #[test]
+        fn level() {
+            let actual = render_post_release_version("alpha", &released("1.2.3")).unwrap();
+            assert_eq!(actual.full_version_string, "1.2.4-alpha.1");
+        }
+
+        #[test]
+        fn template() {
+            let actual =
+                render_post_release_version("{{next_patch}}-dev", &released("1.2.3")).unwrap();
+            assert_eq!(actual.full_version_string, "1.2.4-dev");
+
+            let actual =
+                render_post_release_version("{{next_minor}}-alpha.0", &released("1.2.3")).unwrap();
+            assert_eq!(actual.full_version_string, "1.3.0-alpha.0");
+        }
+
+        #[test]
+        fn invalid_version() {
+            assert!(render_post_release_version("next", &released("1.2.3")).is_err());
+        }
+
+        #[test]
+        fn not_after_release() {
+            assert!(render_post_release_version("{{version}}-dev", &released("1.2.3")).is_err());
+            assert!(render_post_release_version("release", &released("1.2.3")).is_err());
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use crate::config;
+use crate::error::CliError;
+use crate::ops::cargo;
+use crate::steps::journal;
+use crate::steps::plan;
+
+/// Move the released packages on to their `post-release-version` in a separate commit
+pub fn post_release(
+    ws_meta: &cargo_metadata::Metadata,
+    ws_config: &config::Config,
+    journal: &mut journal::Journal,
+    selected_pkgs: &[plan::PackageRelease],
+    excluded_pkgs: &[plan::PackageRelease],
+    consolidate_commits: bool,
+    dry_run: bool,
+) -> Result<(), CliError> {
+    // Bump from the released version, as if it were a release of its own
+    let (next_pkgs, unchanged_pkgs): (Vec<_>, Vec<_>) = selected_pkgs
+        .iter()
+        .map(|pkg| {
+            let mut next = pkg.clone();
+            if let Some(version) = pkg.planned_version.clone() {
+                next.initial_version = version;
+            }
+            next.planned_version = pkg.post_release_version.clone();
+            next
+        })
+        .partition(|pkg| pkg.planned_version.is_some());
+    if next_pkgs.is_empty() {
+        return Ok(());
+    }
+
+    if consolidate_commits {
+        let other_pkgs = unchanged_pkgs
+            .into_iter()
+            .chain(excluded_pkgs.iter().cloned())
+            .collect::<Vec<_>>();
+        journal.run(
+            journal::Action::PostRelease { package: None },
+            dry_run,
+            || {
+                let update_lock =
+                    super::version::update_versions(ws_meta, &next_pkgs, &other_pkgs, dry_run)?;
+                if update_lock {
+                    log::debug!("updating lock file");
+                    if !dry_run {
+                        let workspace_path =
+                            ws_meta.workspace_root.as_std_path().join("Cargo.toml");
+                        cargo::update_lock(&workspace_path)?;
+                    }
+                }
+                super::commit::workspace_commit(
+                    ws_meta,
+                    ws_config,
+                    &next_pkgs,
+                    ws_config.post_release_commit_message(),
+                    dry_run,
+                )
+            },
+        )?;
+    } else {
+        for pkg in &next_pkgs {
+            let crate_name = pkg.meta.name.as_str();
+            let Some(version) = pkg.planned_version.as_ref() else {
+                continue;
+            };
+            journal.run(
+                journal::Action::PostRelease {
+                    package: Some(crate_name.to_owned()),
+                },
+                dry_run,
+                || {
+                    let _ = crate::ops::shell::status(
+                        "Upgrading",
+                        format!(
+                            "{} from {} to {}",
+                            crate_name,
+                            pkg.initial_version.full_version_string,
+                            version.full_version_string
+                        ),
+                    );
+                    cargo::set_package_version(
+                        &pkg.manifest_path,
+                        version.full_version_string.as_str(),
+                        dry_run,
+                    )?;
+                    super::version::update_dependent_versions(ws_meta, pkg, version, dry_run)?;
+                    if dry_run {
+                        log::debug!("updating lock file");
+                    } else {
+                        cargo::update_lock(&pkg.manifest_path)?;
+                    }
+                    super::commit::pkg_commit(
+                        pkg,
+                        pkg.config.post_release_commit_message(),
+                        dry_run,
+                    )
+                },
+            )?;
+        }
+    }
+
+    Ok(())
+}
@@ -1,5 +1,6 @@
 use itertools::Itertools;
 
+use crate::config::PublishWait;
 use crate::error::CliError;
 use crate::ops::git;
 use crate::steps::plan;
@@ -89,19 +90,32 @@ impl PublishStep {
             if pkg.config.release() {
                 let crate_name = pkg.meta.name.as_str();
                 let version = pkg.planned_version.as_ref().unwrap_or(&pkg.initial_version);
-                if crate::ops::cargo::is_published(
-                    &mut index,
-                    pkg.config.registry(),
-                    crate_name,
-                    &version.full_version_string,
-                    pkg.config.certs_source(),
-                ) {
+                let registries = pkg.config.registries();
+                let published =
+                    pkg.published_registries(&version.full_version_string, &mut index)?;
+                if published.len() == registries.len() {
                     let _ = crate::ops::shell::warn(format!(
                         "disabled due to previous publish ({}), skipping {}",
                         version.full_version_string, crate_name
                     ));
                     pkg.config.publish = Some(false);
                     pkg.config.release = Some(false);
+                } else if !published.is_empty() {
+                    let _ = crate::ops::shell::warn(format!(
+                        "skipping {} for {} due to previous publish ({})",
+                        crate_name,
+                        published
+                            .iter()
+                            .map(|r| r.unwrap_or("crates.io"))
+                            .join(", "),
+                        version.full_version_string
+                    ));
+                    let unpublished = registries
+                        .into_iter()
+                        .filter(|r| !published.contains(r))
+                        .collect::<Vec<_>>();
+                    let narrowed = pkg.for_registries(&unpublished);
+                    *pkg = narrowed;
                 }
             }
         }
@@ -160,7 +174,14 @@ impl PublishStep {
         super::confirm("Publish", &selected_pkgs, self.no_confirm, dry_run)?;
 
         // STEP 3: cargo publish
-        publish(&selected_pkgs, dry_run)?;
+        publish(
+            &ws_meta,
+            &ws_config,
+            &selected_pkgs,
+            &mut index,
+            dry_run,
+            &mut |_| Ok(()),
+        )?;
 
         super::finish(failed, dry_run)
     }
@@ -177,34 +198,127 @@ impl PublishStep {
     }
 }
 
-pub fn publish(pkgs: &[plan::PackageRelease], dry_run: bool) -> Result<(), CliError> {
+/// Called with each package as soon as it is uploaded
+pub type OnPublished<'a> = dyn FnMut(&plan::PackageRelease) -> Result<(), CliError> + 'a;
+
+/// Publish `pkgs` to each of their registries
+///
+/// A failure for one registry doesn't stop publishing to the others.
+pub fn publish(
+    ws_meta: &cargo_metadata::Metadata,
+    ws_config: &crate::config::Config,
+    pkgs: &[plan::PackageRelease],
+    index: &mut crate::ops::index::CratesIoIndex,
+    dry_run: bool,
+    on_published: &mut OnPublished<'_>,
+) -> Result<(), CliError> {
+    let mut limiter = RateLimiter::new(&ws_config.rate_limit);
+    let registries = pkgs
+        .iter()
+        .filter(|p| p.config.publish())
+        .flat_map(|p| p.config.registries())
+        .unique()
+        .collect::<Vec<_>>();
+    if let [_] = registries.as_slice() {
+        return publish_to_registry(
+            ws_meta,
+            ws_config,
+            pkgs,
+            index,
+            &mut limiter,
+            dry_run,
+            on_published,
+        );
+    }
+
+    let mut error = None;
+    for registry in &registries {
+        let registry_pkgs = pkgs
+            .iter()
+            .filter(|p| p.config.publish() && p.config.registries().contains(registry))
+            .map(|p| p.for_registries(&[*registry]))
+            .collect::<Vec<_>>();
+        if let Err(err) = publish_to_registry(
+            ws_meta,
+            ws_config,
+            &registry_pkgs,
+            index,
+            &mut limiter,
+            dry_run,
+            on_published,
+        ) {
+            let _ = crate::ops::shell::error(format!(
+                "failed to publish to {}",
+                registry.unwrap_or("crates.io")
+            ));
+            error.get_or_insert(err);
+        }
+    }
+    if let Some(error) = error {
+        return Err(error);
+    }
+    Ok(())
+}
+
+fn publish_to_registry(
+    ws_meta: &cargo_metadata::Metadata,
+    ws_config: &crate::config::Config,
+    pkgs: &[plan::PackageRelease],
+    index: &mut crate::ops::index::CratesIoIndex,
+    limiter: &mut RateLimiter,
+    dry_run: bool,
+    on_published: &mut OnPublished<'_>,
+) -> Result<(), CliError> {
+    let jobs = ws_config.publish_jobs();
     if pkgs.is_empty() {
         Ok(())
+    } else if 1 < jobs && 1 < pkgs.iter().filter(|p| p.config.publish()).count() {
+        parallel_publish(ws_meta, pkgs, index, jobs, limiter, dry_run, on_published)
     } else {
         let first_pkg = pkgs.first().unwrap();
         let registry = first_pkg.config.registry();
         let target = first_pkg.config.target.as_deref();
         let publish_grace_sleep = publish_grace_sleep();
         if publish_grace_sleep.is_none()
-            && pkgs
-                .iter()
-                .all(|p| p.config.registry() == registry && p.config.target.as_deref() == target)
+            && !limiter.throttle
+            && pkgs.iter().all(|p| {
+                p.config.registry() == registry
+                    && p.config.target.as_deref() == target
+                    && p.config.publish_wait() == PublishWait::Cargo
+            })
         {
             let manifest_path = &first_pkg.manifest_path;
-            workspace_publish(manifest_path, pkgs, registry, target, dry_run)
+            workspace_publish(ws_meta, manifest_path, pkgs, index, dry_run, on_published)
         } else {
-            serial_publish(pkgs, publish_grace_sleep, dry_run)
+            serial_publish(
+                ws_meta,
+                pkgs,
+                index,
+                limiter,
+                publish_grace_sleep,
+                dry_run,
+                on_published,
+            )
         }
     }
 }
 
+/// Publish all packages with one `cargo publish`, they share a registry and target
 fn workspace_publish(
+    ws_meta: &cargo_metadata::Metadata,
     manifest_path: &std::path::Path,
     pkgs: &[plan::PackageRelease],
-    registry: Option<&str>,
-    target: Option<&str>,
+    index: &mut crate::ops::index::CratesIoIndex,
     dry_run: bool,
+    on_published: &mut OnPublished<'_>,
 ) -> Result<(), CliError> {
+    let first_pkg = pkgs.first().unwrap();
+    let registry = first_pkg.config.registry();
+    let target = first_pkg.config.target.as_deref();
+    for pkg in pkgs.iter().filter(|p| p.config.publish()) {
+        super::hook::run_hook(ws_meta, pkg, super::hook::HookStage::PrePublish, dry_run)?;
+    }
+
     let crate_names = pkgs.iter().map(|p| p.meta.name.as_str()).join(", ");
     let _ = crate::ops::shell::status("Publishing", crate_names);
 
@@ -215,39 +329,82 @@ fn workspace_publish(
     // trying to specify it.
     // atm at least Cargo doesn't seem to mind if `crate_name` is also a transitive dep, unlike
     // other cargo commands
-    let pkgids = pkgs
+    let mut unpublished = pkgs
         .iter()
         .filter(|p| p.config.publish())
-        .map(|p| p.meta.name.as_str())
         .collect::<Vec<_>>();
-    if !crate::ops::cargo::publish(
-        dry_run,
-        verify,
-        manifest_path,
-        &pkgids,
-        &features,
-        registry,
-        target,
-    )? {
-        return Err(101.into());
+    let max_retries = unpublished
+        .iter()
+        .map(|p| p.config.publish_retries())
+        .min()
+        .unwrap_or(0);
+    let mut retries = 0;
+    loop {
+        let pkgids = unpublished
+            .iter()
+            .map(|p| p.meta.name.as_str())
+            .collect::<Vec<_>>();
+        let Err(err) = crate::ops::cargo::publish(
+            dry_run,
+            verify,
+            manifest_path,
+            &pkgids,
+            &features,
+            registry,
+            target,
+        )?
+        else {
+            break;
+        };
+        if !err.is_transient() || max_retries <= retries {
+            return Err(101.into());
+        }
+        retries += 1;
+        wait_to_retry(&pkgids.join(", "), retries, max_retries);
+
+        // Some may have been published before the failure
+        if !dry_run {
+            unpublished.retain(|pkg| {
+                let published = is_in_index(pkg, index);
+                if published {
+                    let _ = crate::ops::shell::note(format!(
+                        "{} {} was published despite the failure",
+                        pkg.meta.name,
+                        published_version(pkg)
+                    ));
+                }
+                !published
+            });
+            if unpublished.is_empty() {
+                break;
+            }
+        }
+    }
+    for pkg in pkgs.iter().filter(|p| p.config.publish()) {
+        on_published(pkg)?;
+    }
+
+    for pkg in pkgs.iter().filter(|p| p.config.publish()) {
+        super::hook::run_hook(ws_meta, pkg, super::hook::HookStage::PostPublish, dry_run)?;
     }
 
     Ok(())
 }
 
 fn serial_publish(
+    ws_meta: &cargo_metadata::Metadata,
     pkgs: &[plan::PackageRelease],
+    index: &mut crate::ops::index::CratesIoIndex,
+    limiter: &mut RateLimiter,
     publish_grace_sleep: Option<u64>,
     dry_run: bool,
+    on_published: &mut OnPublished<'_>,
 ) -> Result<(), CliError> {
     for pkg in pkgs {
         if !pkg.config.publish() {
             continue;
         }
 
-        let crate_name = pkg.meta.name.as_str();
-        let _ = crate::ops::shell::status("Publishing", crate_name);
-
         let verify = if !pkg.config.verify() {
             false
         } else if dry_run && pkgs.len() != 1 {
@@ -256,15 +413,349 @@ fn serial_publish(
         } else {
             true
         };
-        // feature list to release
-        let features = &[&pkg.features];
-        // HACK: Ignoring the more precise `pkg.meta.id`.  While it has been stabilized,
-        // the version won't match after we do a version bump and it seems too messy to bother
-        // trying to specify it.
-        // atm at least Cargo doesn't seem to mind if `crate_name` is also a transitive dep, unlike
-        // other cargo commands
-        let pkgid = &[crate_name];
-        if !crate::ops::cargo::publish(
+        // Nothing is uploaded in a dry-run, so there is no limit to stay within
+        if !dry_run {
+            let at = limiter.reserve(pkg, index)?;
+            if std::time::Instant::now() < at {
+                let _ = crate::ops::shell::status(
+                    "Waiting",
+                    format!(
+                        "until {} to publish {} within the rate limit of crates.io",
+                        utc_time(at),
+                        pkg.meta.name
+                    ),
+                );
+                std::thread::sleep(at.saturating_duration_since(std::time::Instant::now()));
+            }
+        }
+        super::hook::run_hook(ws_meta, pkg, super::hook::HookStage::PrePublish, dry_run)?;
+        publish_package(pkg, verify, limiter.throttle, dry_run)?;
+        on_published(pkg)?;
+
+        if dry_run {
+            // Nothing was published to wait for
+        } else if pkg.config.publish_wait() == PublishWait::Index {
+            wait_for_index(pkg, index)?;
+        } else if let Some(publish_grace_sleep) = publish_grace_sleep {
+            // HACK: This is a fallback in case users can't or don't want to rely on cargo waiting
+            // for them
+            log::debug!(
+                "waiting an additional {} seconds for {} to update its indices...",
+                publish_grace_sleep,
+                pkg.config.registry().unwrap_or("crates.io")
+            );
+            std::thread::sleep(std::time::Duration::from_secs(publish_grace_sleep));
+        }
+
+        super::hook::run_hook(ws_meta, pkg, super::hook::HookStage::PostPublish, dry_run)?;
+    }
+
+    Ok(())
+}
+
+/// Where a package is in [`parallel_publish`]
+#[derive(Copy, Clone, Debug)]
+enum PublishState {
+    Pending,
+    /// Ready, to be published within the rate limit
+    Scheduled(std::time::Instant),
+    Publishing,
+    /// Published, waiting for it to appear in the index
+    Indexing(IndexPoll),
+    Done,
+}
+
+/// Publish packages concurrently, starting each once its workspace dependencies are in the index
+///
+/// Only `cargo publish` runs on the worker threads, hooks and the index are handled here.
+fn parallel_publish(
+    ws_meta: &cargo_metadata::Metadata,
+    pkgs: &[plan::PackageRelease],
+    index: &mut crate::ops::index::CratesIoIndex,
+    jobs: usize,
+    limiter: &mut RateLimiter,
+    dry_run: bool,
+    on_published: &mut OnPublished<'_>,
+) -> Result<(), CliError> {
+    let pkgs = pkgs
+        .iter()
+        .filter(|p| p.config.publish())
+        .collect::<Vec<_>>();
+    let dependencies = workspace_dependencies(&pkgs);
+    let mut states = vec![PublishState::Pending; pkgs.len()];
+
+    std::thread::scope(|scope| {
+        let (tx, rx) = std::sync::mpsc::channel();
+        let mut publishing = 0;
+        loop {
+            let now = std::time::Instant::now();
+            for (i, pkg) in pkgs.iter().enumerate() {
+                if !matches!(states[i], PublishState::Pending)
+                    || !dependencies[i]
+                        .iter()
+                        .all(|d| matches!(states[*d], PublishState::Done))
+                {
+                    continue;
+                }
+                // Nothing is uploaded in a dry-run, so there is no limit to stay within
+                let at = if dry_run {
+                    now
+                } else {
+                    limiter.reserve(pkg, index)?
+                };
+                if now < at {
+                    let _ = crate::ops::shell::status(
+                        "Waiting",
+                        format!(
+                            "until {} to publish {} within the rate limit of crates.io",
+                            utc_time(at),
+                            pkg.meta.name
+                        ),
+                    );
+                }
+                states[i] = PublishState::Scheduled(at);
+            }
+
+            for (i, pkg) in pkgs.iter().enumerate() {
+                if publishing == jobs {
+                    break;
+                }
+                if !matches!(states[i], PublishState::Scheduled(at) if at <= now) {
+                    continue;
+                }
+                super::hook::run_hook(ws_meta, pkg, super::hook::HookStage::PrePublish, dry_run)?;
+
+                states[i] = PublishState::Publishing;
+                publishing += 1;
+                let tx = tx.clone();
+                // Verifying would fail on the dependencies a dry-run didn't publish
+                let verify = pkg.config.verify() && !dry_run;
+                let throttle = limiter.throttle;
+                scope.spawn(move || {
+                    let result = publish_package(pkg, verify, throttle, dry_run);
+                    let _ = tx.send((i, result));
+                });
+            }
+
+            if states.iter().all(|s| matches!(s, PublishState::Done)) {
+                return Ok(());
+            }
+
+            let next_check = states
+                .iter()
+                .filter_map(|s| match s {
+                    PublishState::Scheduled(at) if now < *at => Some(*at),
+                    PublishState::Indexing(poll) => Some(poll.next_check),
+                    _ => None,
+                })
+                .min();
+            let received = match next_check {
+                Some(next_check) => rx
+                    .recv_timeout(next_check.saturating_duration_since(std::time::Instant::now()))
+                    .ok(),
+                None if publishing == 0 => {
+                    return Err(dependency_cycle(&pkgs, &states));
+                }
+                None => rx.recv().ok(),
+            };
+            if let Some((i, result)) = received {
+                publishing -= 1;
+                // Returning waits on the packages still being published
+                result?;
+                let pkg = pkgs[i];
+                on_published(pkg)?;
+                states[i] = if dry_run {
+                    super::hook::run_hook(
+                        ws_meta,
+                        pkg,
+                        super::hook::HookStage::PostPublish,
+                        dry_run,
+                    )?;
+                    PublishState::Done
+                } else {
+                    start_index_wait(pkg, index)?;
+                    PublishState::Indexing(IndexPoll::new(pkg))
+                };
+            }
+
+            let now = std::time::Instant::now();
+            for (i, pkg) in pkgs.iter().enumerate() {
+                let PublishState::Indexing(poll) = &mut states[i] else {
+                    continue;
+                };
+                if now < poll.next_check {
+                    continue;
+                }
+                if is_in_index(pkg, index) {
+                    super::hook::run_hook(
+                        ws_meta,
+                        pkg,
+                        super::hook::HookStage::PostPublish,
+                        dry_run,
+                    )?;
+                    states[i] = PublishState::Done;
+                } else if !poll.backoff() {
+                    return Err(index_timeout(pkg));
+                }
+            }
+        }
+    })
+}
+
+/// For each package, the indices of the packages it depends on
+///
+/// Like [`crate::ops::cargo::sort_workspace`], dev-dependencies are ignored.
+fn workspace_dependencies(pkgs: &[&plan::PackageRelease]) -> Vec<Vec<usize>> {
+    pkgs.iter()
+        .map(|pkg| {
+            pkg.meta
+                .dependencies
+                .iter()
+                .filter(|dep| {
+                    dep.path.is_some() && dep.kind != cargo_metadata::DependencyKind::Development
+                })
+                .filter_map(|dep| pkgs.iter().position(|p| p.meta.name.as_str() == dep.name))
+                .unique()
+                .collect()
+        })
+        .collect()
+}
+
+/// Schedules publishes to crates.io within its rate limits
+///
+/// The limits are only known for the default registry.
+#[derive(Clone, Debug)]
+pub struct RateLimiter {
+    new_packages: TokenBucket,
+    existing_packages: TokenBucket,
+    /// Wait for the limits rather than failing
+    throttle: bool,
+}
+
+impl RateLimiter {
+    /// How often crates.io allows publishing a new crate, once the burst is used up
+    const NEW_PACKAGES_REFILL: std::time::Duration = std::time::Duration::from_secs(10 * 60);
+    /// How often crates.io allows publishing an existing crate, once the burst is used up
+    const EXISTING_PACKAGES_REFILL: std::time::Duration = std::time::Duration::from_secs(60);
+
+    pub fn new(rate_limit: &crate::config::RateLimit) -> Self {
+        let now = std::time::Instant::now();
+        Self {
+            new_packages: TokenBucket::new(
+                rate_limit.new_packages(),
+                Self::NEW_PACKAGES_REFILL,
+                now,
+            ),
+            existing_packages: TokenBucket::new(
+                rate_limit.existing_packages(),
+                Self::EXISTING_PACKAGES_REFILL,
+                now,
+            ),
+            throttle: rate_limit.throttle(),
+        }
+    }
+
+    /// Reserve the next time `pkg` may be published
+    ///
+    /// Unless throttling, going above the limits is an error.
+    pub fn reserve(
+        &mut self,
+        pkg: &plan::PackageRelease,
+        index: &mut crate::ops::index::CratesIoIndex,
+    ) -> Result<std::time::Instant, CliError> {
+        let now = std::time::Instant::now();
+        if !pkg.config.registries().contains(&None) {
+            return Ok(now);
+        }
+        let crate_name = pkg.meta.name.as_str();
+        let (bucket, kind) = if index.has_krate(None, crate_name, pkg.config.certs_source())? {
+            (&mut self.existing_packages, "existing")
+        } else {
+            (&mut self.new_packages, "new")
+        };
+        let at = bucket.take(now);
+        if now < at && !self.throttle {
+            let _ = crate::ops::shell::error(format!(
+                "publishing {crate_name} would go above the rate limit for {kind} crates"
+            ));
+            return Err(101.into());
+        }
+        Ok(at)
+    }
+}
+
+/// Allows a burst of events, then one per `interval`
+///
+/// This tracks the theoretical time of the next event, like the generic cell rate algorithm.
+#[derive(Copy, Clone, Debug)]
+struct TokenBucket {
+    interval: std::time::Duration,
+    /// How far ahead of `next` an event may happen, from the burst
+    tolerance: std::time::Duration,
+    next: std::time::Instant,
+}
+
+impl TokenBucket {
+    fn new(burst: usize, interval: std::time::Duration, now: std::time::Instant) -> Self {
+        let burst = u32::try_from(burst.max(1)).unwrap_or(u32::MAX);
+        Self {
+            interval,
+            tolerance: interval.saturating_mul(burst - 1),
+            next: now,
+        }
+    }
+
+    /// Take a token, returning when it is available
+    fn take(&mut self, now: std::time::Instant) -> std::time::Instant {
+        let next = self.next.max(now);
+        let at = if next <= now + self.tolerance {
+            now
+        } else {
+            next - self.tolerance
+        };
+        self.next = next + self.interval;
+        at
+    }
+}
+
+const UTC_TIME: &[time::format_description::BorrowedFormatItem<'static>] =
+    time::macros::format_description!("[hour]:[minute] UTC");
+
+/// Render `at` as a wall-clock time
+pub fn utc_time(at: std::time::Instant) -> String {
+    let wait = at.saturating_duration_since(std::time::Instant::now());
+    let at = time::OffsetDateTime::now_utc() + wait;
+    at.format(UTC_TIME).unwrap_or_else(|_| at.to_string())
+}
+
+/// Run `cargo publish` for a single package
+///
+/// Transient failures are retried up to `publish-retries` times.
+/// When `throttle`d, publishing is also retried when the registry asks to try again later.
+fn publish_package(
+    pkg: &plan::PackageRelease,
+    verify: bool,
+    throttle: bool,
+    dry_run: bool,
+) -> Result<(), CliError> {
+    const MAX_RATE_LIMIT_RETRIES: usize = 3;
+
+    let crate_name = pkg.meta.name.as_str();
+    let _ = crate::ops::shell::status("Publishing", crate_name);
+
+    // feature list to release
+    let features = &[&pkg.features];
+    // HACK: Ignoring the more precise `pkg.meta.id`.  While it has been stabilized,
+    // the version won't match after we do a version bump and it seems too messy to bother
+    // trying to specify it.
+    // atm at least Cargo doesn't seem to mind if `crate_name` is also a transitive dep, unlike
+    // other cargo commands
+    let pkgid = &[crate_name];
+    let mut retries = 0;
+    let mut rate_limit_retries = 0;
+    let mut index = None;
+    loop {
+        let Err(err) = crate::ops::cargo::publish(
             dry_run,
             verify,
             &pkg.manifest_path,
@@ -272,23 +763,194 @@ fn serial_publish(
             features,
             pkg.config.registry(),
             pkg.config.target.as_ref().map(AsRef::as_ref),
-        )? {
+        )?
+        else {
+            return Ok(());
+        };
+
+        if let Some(retry_after) = err
+            .retry_after()
+            .filter(|_| throttle && rate_limit_retries < MAX_RATE_LIMIT_RETRIES)
+        {
+            rate_limit_retries += 1;
+            let _ = crate::ops::shell::status(
+                "Waiting",
+                format!(
+                    "until {} to retry publishing {crate_name}, as asked by {}",
+                    retry_after
+                        .format(UTC_TIME)
+                        .unwrap_or_else(|_| retry_after.to_string()),
+                    pkg.config.registry().unwrap_or("crates.io")
+                ),
+            );
+            let wait = retry_after - time::OffsetDateTime::now_utc();
+            std::thread::sleep(wait.try_into().unwrap_or_default());
+        } else if err.is_transient() && retries < pkg.config.publish_retries() {
+            retries += 1;
+            wait_to_retry(crate_name, retries, pkg.config.publish_retries());
+        } else {
             return Err(101.into());
         }
 
-        // HACK: This is a fallback in case users can't or don't want to rely on cargo waiting for
-        // them
-        if !dry_run && let Some(publish_grace_sleep) = publish_grace_sleep {
+        // The registry may have accepted it before the failure
+        if !dry_run && is_in_index(pkg, index.get_or_insert_with(Default::default)) {
+            let _ = crate::ops::shell::note(format!(
+                "{crate_name} {} was published despite the failure",
+                published_version(pkg)
+            ));
+            return Ok(());
+        }
+    }
+}
+
+/// Back off before retrying a transient failure
+fn wait_to_retry(crate_names: &str, retry: usize, max_retries: usize) {
+    const INITIAL_DELAY: std::time::Duration = std::time::Duration::from_secs(2);
+    const MAX_DELAY: std::time::Duration = std::time::Duration::from_secs(60);
+
+    let exponent = u32::try_from(retry - 1).unwrap_or(u32::MAX);
+    let delay = INITIAL_DELAY
+        .saturating_mul(2_u32.saturating_pow(exponent))
+        .min(MAX_DELAY);
+    let _ = crate::ops::shell::status(
+        "Waiting",
+        format!(
+            "{}s to retry publishing {crate_names} ({retry}/{max_retries})",
+            delay.as_secs()
+        ),
+    );
+    std::thread::sleep(delay);
+}
+
+/// Poll the index until the version of `pkg` that was just published shows up
+fn wait_for_index(
+    pkg: &plan::PackageRelease,
+    index: &mut crate::ops::index::CratesIoIndex,
+) -> Result<(), CliError> {
+    start_index_wait(pkg, index)?;
+
+    let mut poll = IndexPoll::new(pkg);
+    loop {
+        if is_in_index(pkg, index) {
+            return Ok(());
+        }
+        if !poll.backoff() {
+            return Err(index_timeout(pkg));
+        }
+        std::thread::sleep(
+            poll.next_check
+                .saturating_duration_since(std::time::Instant::now()),
+        );
+    }
+}
+
+/// Connect to the index of `pkg`'s registry, before polling it for the published version
+fn start_index_wait(
+    pkg: &plan::PackageRelease,
+    index: &mut crate::ops::index::CratesIoIndex,
+) -> Result<(), CliError> {
+    let registry = pkg.config.registry();
+    index.connect(registry, pkg.config.certs_source())?;
+    let _ = crate::ops::shell::status(
+        "Waiting",
+        format!(
+            "for {} {} to appear in {}",
+            pkg.meta.name,
+            published_version(pkg),
+            registry.unwrap_or("crates.io")
+        ),
+    );
+    Ok(())
+}
+
+fn is_in_index(pkg: &plan::PackageRelease, index: &mut crate::ops::index::CratesIoIndex) -> bool {
+    let registry = pkg.config.registry();
+    let crate_name = pkg.meta.name.as_str();
+    let version = published_version(pkg);
+    index.update_krate(registry, crate_name);
+    match index.has_krate_version(registry, crate_name, version, pkg.config.certs_source()) {
+        Ok(Some(true)) => true,
+        Ok(_) => {
             log::debug!(
-                "waiting an additional {} seconds for {} to update its indices...",
-                publish_grace_sleep,
-                pkg.config.registry().unwrap_or("crates.io")
+                "{crate_name} {version} is not in {} yet",
+                registry.unwrap_or("crates.io")
             );
-            std::thread::sleep(std::time::Duration::from_secs(publish_grace_sleep));
+            false
+        }
+        // Keep polling through hiccups, the timeout bounds how long we try
+        Err(err) => {
+            log::debug!(
+                "failed to read the index of {}: {err}",
+                registry.unwrap_or("crates.io")
+            );
+            false
         }
     }
+}
 
-    Ok(())
+/// Nothing can make progress when the pending packages depend on each other
+fn dependency_cycle(pkgs: &[&plan::PackageRelease], states: &[PublishState]) -> CliError {
+    let pending = pkgs
+        .iter()
+        .zip(states)
+        .filter(|(_, state)| matches!(state, PublishState::Pending))
+        .map(|(pkg, _)| pkg.meta.name.as_str())
+        .join(", ");
+    let _ = crate::ops::shell::error(format!(
+        "cannot publish {pending}, their workspace dependencies form a cycle"
+    ));
+    101.into()
+}
+
+fn index_timeout(pkg: &plan::PackageRelease) -> CliError {
+    let _ = crate::ops::shell::error(format!(
+        "timed out after {}s waiting for {} {} to appear in {}",
+        pkg.config.publish_timeout(),
+        pkg.meta.name,
+        published_version(pkg),
+        pkg.config.registry().unwrap_or("crates.io")
+    ));
+    101.into()
+}
+
+fn published_version(pkg: &plan::PackageRelease) -> &str {
+    &pkg.planned_version
+        .as_ref()
+        .unwrap_or(&pkg.initial_version)
+        .full_version_string
+}
+
+/// Exponential backoff between checks of the index, bounded by `publish-timeout`
+#[derive(Copy, Clone, Debug)]
+struct IndexPoll {
+    deadline: std::time::Instant,
+    delay: std::time::Duration,
+    next_check: std::time::Instant,
+}
+
+impl IndexPoll {
+    const INITIAL_DELAY: std::time::Duration = std::time::Duration::from_secs(1);
+    const MAX_DELAY: std::time::Duration = std::time::Duration::from_secs(30);
+
+    fn new(pkg: &plan::PackageRelease) -> Self {
+        let now = std::time::Instant::now();
+        Self {
+            deadline: now + std::time::Duration::from_secs(pkg.config.publish_timeout()),
+            delay: Self::INITIAL_DELAY,
+            next_check: now,
+        }
+    }
+
+    /// Schedule the next check, returning `false` once the timeout has passed
+    fn backoff(&mut self) -> bool {
+        let now = std::time::Instant::now();
+        if self.deadline <= now {
+            return false;
+        }
+        self.next_check = (now + self.delay).min(self.deadline);
+        self.delay = (self.delay * 2).min(Self::MAX_DELAY);
+        true
+    }
 }
 
 fn publish_grace_sleep() -> Option<u64> {
@@ -302,3 +964,36 @@ fn publish_grace_sleep() -> Option<u64> {
         Some(publish_grace_sleep)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    mod token_bucket {
+        use super::*;
+
+        const MINUTE: std::time::Duration = std::time::Duration::from_secs(60);
+
+        #[test]
+        fn burst() {
+            let now = std::time::Instant::now();
+            let mut bucket = TokenBucket::new(2, MINUTE, now);
+            assert_eq!(bucket.take(now), now);
+            assert_eq!(bucket.take(now), now);
+            assert_eq!(bucket.take(now), now + MINUTE);
+            assert_eq!(bucket.take(now), now + 2 * MINUTE);
+        }
+
+        #[test]
+        fn refill() {
+            let now = std::time::Instant::now();
+            let mut bucket = TokenBucket::new(2, MINUTE, now);
+            bucket.take(now);
+            bucket.take(now);
+            let later = now + 3 * MINUTE;
+            assert_eq!(bucket.take(later), later);
+            assert_eq!(bucket.take(later), later);
+            assert_eq!(bucket.take(later), later + MINUTE);
+        }
+    }
+}
@@ -187,6 +187,10 @@ pub fn push(
                 return Err(101.into());
             }
         }
+
+        for pkg in pkgs.iter().filter(|p| p.config.push()) {
+            super::hook::run_hook(ws_meta, pkg, super::hook::HookStage::PostPush, dry_run)?;
+        }
     }
 
     Ok(())
@@ -2,6 +2,8 @@ use crate::config;
 use crate::error::CliError;
 use crate::ops::cargo;
 use crate::ops::git;
+use crate::steps::hook::HookStage;
+use crate::steps::journal;
 use crate::steps::plan;
 
 #[derive(Debug, Clone, clap::Args)]
@@ -39,6 +41,25 @@ pub struct ReleaseStep {
     #[arg(long, value_name = "NAME")]
     prev_tag_name: Option<String>,
 
+    /// Write the resolved release plan to PATH for review
+    #[arg(long, value_name = "PATH", conflicts_with_all = ["plan", "resume"])]
+    plan_out: Option<std::path::PathBuf>,
+
+    /// Carry out the release plan from `--plan-out`, refusing if anything changed since
+    #[arg(
+        long,
+        value_name = "PATH",
+        conflicts_with_all = ["level_or_version", "unpublished", "prev_tag_name", "package", "workspace", "exclude"],
+    )]
+    plan: Option<std::path::PathBuf>,
+
+    /// Pick up an interrupted release at its first incomplete action
+    #[arg(
+        long,
+        conflicts_with_all = ["level_or_version", "unpublished", "prev_tag_name", "package", "workspace", "exclude", "plan"],
+    )]
+    resume: bool,
+
     #[command(flatten)]
     config: config::ConfigArgs,
 }
@@ -71,24 +92,27 @@ impl ReleaseStep {
             if pkg.config.release()
                 && let Some(level_or_version) = &self.level_or_version
             {
-                pkg.bump(level_or_version, self.metadata.as_deref())?;
+                pkg.bump(&ws_meta, level_or_version, self.metadata.as_deref())?;
+            }
+            let mut published = true;
+            for registry in pkg.config.registries() {
+                published &=
+                    index.has_krate(registry, &pkg.meta.name, pkg.config.certs_source())?;
             }
-            if index.has_krate(
-                pkg.config.registry(),
-                &pkg.meta.name,
-                pkg.config.certs_source(),
-            )? {
+            if published {
                 // Already published, skip it.  Use `cargo release owner` for one-time updates
                 pkg.ensure_owners = false;
             }
         }
 
-        let (_selected_pkgs, excluded_pkgs) =
-            if self.unpublished && self.workspace == clap_cargo::Workspace::default() {
-                ws_meta.packages.iter().partition(|_| false)
-            } else {
-                self.workspace.partition_packages(&ws_meta)
-            };
+        let (_selected_pkgs, excluded_pkgs) = if self.plan.is_some() || self.resume {
+            // Selection comes from the plan
+            (Vec::new(), Vec::new())
+        } else if self.unpublished && self.workspace == clap_cargo::Workspace::default() {
+            ws_meta.packages.iter().partition(|_| false)
+        } else {
+            self.workspace.partition_packages(&ws_meta)
+        };
         for excluded_pkg in &excluded_pkgs {
             let Some(pkg) = pkgs.get_mut(&excluded_pkg.id) else {
                 // Either not in workspace or marked as `release = false`.
@@ -108,13 +132,11 @@ impl ReleaseStep {
                 && !explicitly_excluded
             {
                 let version = &pkg.initial_version;
-                if !cargo::is_published(
-                    &mut index,
-                    pkg.config.registry(),
-                    crate_name,
-                    &version.full_version_string,
-                    pkg.config.certs_source(),
-                ) {
+                if pkg
+                    .published_registries(&version.full_version_string, &mut index)?
+                    .len()
+                    < pkg.config.registries().len()
+                {
                     log::debug!(
                         "enabled {}, v{} is unpublished",
                         crate_name,
@@ -150,7 +172,48 @@ impl ReleaseStep {
             }
         }
 
-        let pkgs = plan::plan(pkgs)?;
+        let mut pkgs = plan::plan(pkgs)?;
+
+        if let Some(plan_path) = self.plan.as_deref() {
+            let frozen = plan::FrozenPlan::load(plan_path)?;
+            let drift = frozen.drift(&ws_meta, &pkgs, &mut index)?;
+            if !drift.is_empty() {
+                for drift in drift {
+                    let _ = crate::ops::shell::error(drift);
+                }
+                let _ = crate::ops::shell::error(format!(
+                    "workspace has changed since `{}` was written, regenerate it with `--plan-out`",
+                    plan_path.display()
+                ));
+                return Err(101.into());
+            }
+            frozen.apply(&mut pkgs)?;
+        }
+
+        let dry_run = !self.execute;
+        let journal_path = journal::Journal::path(ws_meta.workspace_root.as_std_path())?;
+        let resumed_journal = if self.resume {
+            let Some(journal) = journal::Journal::load(&journal_path)? else {
+                let _ = crate::ops::shell::error("no interrupted release to resume");
+                return Err(101.into());
+            };
+            journal.plan.apply(&mut pkgs)?;
+            Some(journal)
+        } else {
+            if journal_path.exists() {
+                let message = format!(
+                    "a release was interrupted, pass `--resume` to continue it or remove `{}`",
+                    journal_path.display()
+                );
+                if dry_run {
+                    let _ = crate::ops::shell::warn(message);
+                } else {
+                    let _ = crate::ops::shell::error(message);
+                    return Err(101.into());
+                }
+            }
+            None
+        };
 
         for excluded_pkg in &excluded_pkgs {
             let Some(pkg) = pkgs.get(&excluded_pkg.id) else {
@@ -158,17 +221,14 @@ impl ReleaseStep {
                 continue;
             };
 
-            // HACK: `index` only supports default registry
-            if pkg.config.publish() && pkg.config.registry().is_none() {
+            if pkg.config.publish() {
                 let version = pkg.planned_version.as_ref().unwrap_or(&pkg.initial_version);
                 let crate_name = pkg.meta.name.as_str();
-                if !cargo::is_published(
-                    &mut index,
-                    pkg.config.registry(),
-                    crate_name,
-                    &version.full_version_string,
-                    pkg.config.certs_source(),
-                ) {
+                if pkg
+                    .published_registries(&version.full_version_string, &mut index)?
+                    .len()
+                    < pkg.config.registries().len()
+                {
                     let _ = crate::ops::shell::warn(format!(
                         "disabled by user, skipping {} v{} despite being unpublished",
                         crate_name, version.full_version_string,
@@ -177,6 +237,15 @@ impl ReleaseStep {
             }
         }
 
+        if let Some(plan_out) = self.plan_out.as_deref() {
+            let frozen = plan::FrozenPlan::new(&ws_meta, pkgs.values(), &mut index)?;
+            frozen.save(plan_out)?;
+            let _ = crate::ops::shell::status(
+                "Writing",
+                format!("release plan to {}", plan_out.display()),
+            );
+        }
+
         let (selected_pkgs, excluded_pkgs): (Vec<_>, Vec<_>) = pkgs
             .into_iter()
             .map(|(_, pkg)| pkg)
@@ -186,7 +255,6 @@ impl ReleaseStep {
             return Err(2.into());
         }
 
-        let dry_run = !self.execute;
         let mut failed = false;
 
         let consolidate_commits = super::consolidate_commits(&selected_pkgs, &excluded_pkgs)?;
@@ -201,35 +269,37 @@ impl ReleaseStep {
             log::Level::Error,
         )?;
 
+        // An interrupted release may have left its edits uncommitted and its tags created
         failed |= !super::verify_git_is_clean(
             ws_meta.workspace_root.as_std_path(),
             dry_run,
-            log::Level::Error,
+            if self.resume {
+                log::Level::Warn
+            } else {
+                log::Level::Error
+            },
         )?;
 
-        failed |= !super::verify_tags_missing(&selected_pkgs, dry_run, log::Level::Error)?;
+        if !self.resume {
+            failed |= !super::verify_tags_missing(&selected_pkgs, dry_run, log::Level::Error)?;
+        }
 
         failed |=
             !super::verify_monotonically_increasing(&selected_pkgs, dry_run, log::Level::Error)?;
 
         let mut double_publish = false;
         for pkg in &selected_pkgs {
-            if !pkg.config.publish() {
+            if !pkg.config.publish() || self.resume {
                 continue;
             }
             let version = pkg.planned_version.as_ref().unwrap_or(&pkg.initial_version);
             let crate_name = pkg.meta.name.as_str();
-            if cargo::is_published(
-                &mut index,
-                pkg.config.registry(),
-                crate_name,
-                &version.full_version_string,
-                pkg.config.certs_source(),
-            ) {
-                let registry = pkg.config.registry().unwrap_or("crates.io");
+            for registry in pkg.published_registries(&version.full_version_string, &mut index)? {
                 let _ = crate::ops::shell::error(format!(
                     "{} {} is already published to {}",
-                    crate_name, version.full_version_string, registry
+                    crate_name,
+                    version.full_version_string,
+                    registry.unwrap_or("crates.io")
                 ));
                 double_publish = true;
             }
@@ -269,10 +339,156 @@ impl ReleaseStep {
         // STEP 1: Release Confirmation
         super::confirm("Release", &selected_pkgs, self.no_confirm, dry_run)?;
 
+        // Rolling back must not touch edits the release didn't make nor redo a resumed release
+        let can_roll_back =
+            !self.resume && git::is_dirty(ws_meta.workspace_root.as_std_path())?.is_none();
+        let mut journal = if let Some(journal) = resumed_journal {
+            journal
+        } else {
+            let frozen = plan::FrozenPlan::new(&ws_meta, &selected_pkgs, &mut index)?;
+            journal::Journal::new(journal_path, frozen)
+        };
+        journal.save(dry_run)?;
+
         // STEP 2: update current version, save and commit
-        if consolidate_commits {
+        if let Err(err) = update_and_commit(
+            &ws_meta,
+            &ws_config,
+            &mut journal,
+            &selected_pkgs,
+            &excluded_pkgs,
+            consolidate_commits,
+            dry_run,
+        ) {
+            rollback_on_error(&ws_meta, &journal, &selected_pkgs, can_roll_back, dry_run);
+            return Err(err);
+        }
+
+        // STEP 3: cargo publish
+        let mut unpublished_pkgs = Vec::new();
+        for pkg in &selected_pkgs {
+            let crate_name = pkg.meta.name.as_str();
+            let action = journal::Action::Publish {
+                package: crate_name.to_owned(),
+            };
+            if !pkg.config.publish() {
+                continue;
+            }
+            if journal.is_done(&action) {
+                let _ = crate::ops::shell::status("Skipping", format!("{action}, already done"));
+                continue;
+            }
+            let version = pkg.planned_version.as_ref().unwrap_or(&pkg.initial_version);
+            let registries = pkg.config.registries();
+            let published = if self.resume {
+                pkg.published_registries(&version.full_version_string, &mut index)?
+            } else {
+                Vec::new()
+            };
+            if published.len() == registries.len() {
+                let _ = crate::ops::shell::status(
+                    "Skipping",
+                    format!(
+                        "{action}, {} is already published",
+                        version.full_version_string
+                    ),
+                );
+                journal.record(action, dry_run)?;
+                continue;
+            }
+            if published.is_empty() {
+                unpublished_pkgs.push(pkg.clone());
+            } else {
+                // Finish publishing to the registries that were missed
+                let unpublished = registries
+                    .into_iter()
+                    .filter(|r| !published.contains(r))
+                    .collect::<Vec<_>>();
+                unpublished_pkgs.push(pkg.for_registries(&unpublished));
+            }
+        }
+        let published = super::publish::publish(
+            &ws_meta,
+            &ws_config,
+            &unpublished_pkgs,
+            &mut index,
+            dry_run,
+            &mut |pkg| {
+                let package = pkg.meta.name.as_str().to_owned();
+                journal.record(journal::Action::Publish { package }, dry_run)?;
+                Ok(())
+            },
+        );
+        if let Err(err) = published {
+            rollback_on_error(&ws_meta, &journal, &selected_pkgs, can_roll_back, dry_run);
+            return Err(err);
+        }
+        super::owner::ensure_owners(&unpublished_pkgs, dry_run)?;
+
+        // STEP 5: Tag
+        let mut untagged_pkgs = Vec::new();
+        for pkg in &selected_pkgs {
+            let action = journal::Action::Tag {
+                package: pkg.meta.name.as_str().to_owned(),
+            };
+            let Some(tag_name) = pkg.planned_tag.as_deref() else {
+                continue;
+            };
+            if journal.is_done(&action) {
+                let _ = crate::ops::shell::status("Skipping", format!("{action}, already done"));
+                continue;
+            }
+            if self.resume && git::tag_exists(&pkg.package_root, tag_name)? {
+                let _ = crate::ops::shell::status(
+                    "Skipping",
+                    format!("{action}, {tag_name} already exists"),
+                );
+                journal.record(action, dry_run)?;
+                continue;
+            }
+            untagged_pkgs.push(pkg.clone());
+        }
+        super::tag::tag(&ws_meta, &untagged_pkgs, dry_run, &mut |pkg| {
+            let package = pkg.meta.name.as_str().to_owned();
+            journal.record(journal::Action::Tag { package }, dry_run)?;
+            Ok(())
+        })?;
+
+        // STEP 6: Move on to the next development version
+        super::post_release::post_release(
+            &ws_meta,
+            &ws_config,
+            &mut journal,
+            &selected_pkgs,
+            &excluded_pkgs,
+            consolidate_commits,
+            dry_run,
+        )?;
+
+        // STEP 7: git push
+        journal.run(journal::Action::Push, dry_run, || {
+            super::push::push(&ws_config, &ws_meta, &selected_pkgs, dry_run)
+        })?;
+
+        journal.finish(dry_run)?;
+
+        super::finish(failed, dry_run)
+    }
+}
+
+fn update_and_commit(
+    ws_meta: &cargo_metadata::Metadata,
+    ws_config: &config::Config,
+    journal: &mut journal::Journal,
+    selected_pkgs: &[plan::PackageRelease],
+    excluded_pkgs: &[plan::PackageRelease],
+    consolidate_commits: bool,
+    dry_run: bool,
+) -> Result<(), CliError> {
+    if consolidate_commits {
+        journal.run(journal::Action::Version { package: None }, dry_run, || {
             let update_lock =
-                super::version::update_versions(&ws_meta, &selected_pkgs, &excluded_pkgs, dry_run)?;
+                super::version::update_versions(ws_meta, selected_pkgs, excluded_pkgs, dry_run)?;
             if update_lock {
                 log::debug!("updating lock file");
                 if !dry_run {
@@ -280,62 +496,183 @@ impl ReleaseStep {
                     cargo::update_lock(&workspace_path)?;
                 }
             }
+            Ok(())
+        })?;
+
+        for pkg in selected_pkgs {
+            let package = pkg.meta.name.as_str().to_owned();
+            journal.run(
+                journal::Action::Changelog {
+                    package: package.clone(),
+                },
+                dry_run,
+                || super::changelog::changelog(ws_meta, pkg, dry_run),
+            )?;
+
+            journal.run(
+                journal::Action::Replace {
+                    package: package.clone(),
+                },
+                dry_run,
+                || super::replace::replace(pkg, dry_run),
+            )?;
+
+            // pre-release hook
+            journal.run(
+                journal::Action::Hook {
+                    package,
+                    stage: HookStage::PreRelease,
+                },
+                dry_run,
+                || super::hook::hook(ws_meta, pkg, dry_run),
+            )?;
+        }
 
-            for pkg in &selected_pkgs {
-                super::replace::replace(pkg, dry_run)?;
+        if ws_config.workspace_pre_release_hook().is_some() {
+            journal.run(journal::Action::WorkspaceHook, dry_run, || {
+                super::hook::workspace_hook(ws_meta, ws_config, selected_pkgs, dry_run)
+            })?;
+        }
 
-                // pre-release hook
-                super::hook::hook(&ws_meta, pkg, dry_run)?;
+        journal.run(journal::Action::Commit { package: None }, dry_run, || {
+            super::commit::workspace_commit(
+                ws_meta,
+                ws_config,
+                selected_pkgs,
+                ws_config.pre_release_commit_message(),
+                dry_run,
+            )
+        })?;
+
+        for pkg in selected_pkgs
+            .iter()
+            .filter(|p| p.config.post_commit_hook().is_some())
+        {
+            journal.run(
+                journal::Action::Hook {
+                    package: pkg.meta.name.as_str().to_owned(),
+                    stage: HookStage::PostCommit,
+                },
+                dry_run,
+                || super::hook::run_hook(ws_meta, pkg, HookStage::PostCommit, dry_run),
+            )?;
+        }
+    } else {
+        for (i, pkg) in selected_pkgs.iter().enumerate() {
+            let package = pkg.meta.name.as_str().to_owned();
+            if let Some(version) = pkg.planned_version.as_ref() {
+                journal.run(
+                    journal::Action::Version {
+                        package: Some(package.clone()),
+                    },
+                    dry_run,
+                    || {
+                        let crate_name = pkg.meta.name.as_str();
+                        let _ = crate::ops::shell::status(
+                            "Upgrading",
+                            format!(
+                                "{} from {} to {}",
+                                crate_name,
+                                pkg.initial_version.full_version_string,
+                                version.full_version_string
+                            ),
+                        );
+                        cargo::set_package_version(
+                            &pkg.manifest_path,
+                            version.full_version_string.as_str(),
+                            dry_run,
+                        )?;
+                        crate::steps::version::update_dependent_versions(
+                            ws_meta, pkg, version, dry_run,
+                        )?;
+                        if dry_run {
+                            log::debug!("updating lock file");
+                        } else {
+                            cargo::update_lock(&pkg.manifest_path)?;
+                        }
+                        Ok(())
+                    },
+                )?;
             }
 
-            super::commit::workspace_commit(&ws_meta, &ws_config, &selected_pkgs, dry_run)?;
-        } else {
-            for pkg in &selected_pkgs {
-                if let Some(version) = pkg.planned_version.as_ref() {
-                    let crate_name = pkg.meta.name.as_str();
-                    let _ = crate::ops::shell::status(
-                        "Upgrading",
-                        format!(
-                            "{} from {} to {}",
-                            crate_name,
-                            pkg.initial_version.full_version_string,
-                            version.full_version_string
-                        ),
-                    );
-                    cargo::set_package_version(
-                        &pkg.manifest_path,
-                        version.full_version_string.as_str(),
-                        dry_run,
-                    )?;
-                    crate::steps::version::update_dependent_versions(
-                        &ws_meta, pkg, version, dry_run,
-                    )?;
-                    if dry_run {
-                        log::debug!("updating lock file");
-                    } else {
-                        cargo::update_lock(&pkg.manifest_path)?;
-                    }
-                }
-
-                super::replace::replace(pkg, dry_run)?;
-
-                // pre-release hook
-                super::hook::hook(&ws_meta, pkg, dry_run)?;
+            journal.run(
+                journal::Action::Changelog {
+                    package: package.clone(),
+                },
+                dry_run,
+                || super::changelog::changelog(ws_meta, pkg, dry_run),
+            )?;
+
+            journal.run(
+                journal::Action::Replace {
+                    package: package.clone(),
+                },
+                dry_run,
+                || super::replace::replace(pkg, dry_run),
+            )?;
+
+            // pre-release hook
+            journal.run(
+                journal::Action::Hook {
+                    package: package.clone(),
+                    stage: HookStage::PreRelease,
+                },
+                dry_run,
+                || super::hook::hook(ws_meta, pkg, dry_run),
+            )?;
+
+            // Once every package is updated, so its changes land in the last release commit
+            if i + 1 == selected_pkgs.len() && ws_config.workspace_pre_release_hook().is_some() {
+                journal.run(journal::Action::WorkspaceHook, dry_run, || {
+                    super::hook::workspace_hook(ws_meta, ws_config, selected_pkgs, dry_run)
+                })?;
+            }
 
-                super::commit::pkg_commit(pkg, dry_run)?;
+            journal.run(
+                journal::Action::Commit {
+                    package: Some(package.clone()),
+                },
+                dry_run,
+                || super::commit::pkg_commit(pkg, pkg.config.pre_release_commit_message(), dry_run),
+            )?;
+
+            if pkg.config.post_commit_hook().is_some() {
+                journal.run(
+                    journal::Action::Hook {
+                        package,
+                        stage: HookStage::PostCommit,
+                    },
+                    dry_run,
+                    || super::hook::run_hook(ws_meta, pkg, HookStage::PostCommit, dry_run),
+                )?;
             }
         }
+    }
 
-        // STEP 3: cargo publish
-        super::publish::publish(&selected_pkgs, dry_run)?;
-        super::owner::ensure_owners(&selected_pkgs, dry_run)?;
-
-        // STEP 5: Tag
-        super::tag::tag(&selected_pkgs, dry_run)?;
-
-        // STEP 6: git push
-        super::push::push(&ws_config, &ws_meta, &selected_pkgs, dry_run)?;
+    Ok(())
+}
 
-        super::finish(failed, dry_run)
+/// Undo the local changes of a release that failed before anything left the machine
+fn rollback_on_error(
+    ws_meta: &cargo_metadata::Metadata,
+    journal: &journal::Journal,
+    selected_pkgs: &[plan::PackageRelease],
+    can_roll_back: bool,
+    dry_run: bool,
+) {
+    if dry_run {
+        return;
+    }
+    if !can_roll_back {
+        let _ = crate::ops::shell::note(
+            "run `cargo release --resume` to finish the release or `cargo release undo` to roll it back",
+        );
+        return;
+    }
+    let _ = crate::ops::shell::status("Rolling back", "release");
+    if super::undo::rollback(ws_meta, journal, selected_pkgs, dry_run).is_err() {
+        let _ = crate::ops::shell::note(
+            "run `cargo release --resume` to finish the release or `cargo release undo` to retry the rollback",
+        );
     }
 }
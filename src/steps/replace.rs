@@ -89,13 +89,11 @@ impl ReplaceStep {
                 && !explicitly_excluded
             {
                 let version = &pkg.initial_version;
-                if !crate::ops::cargo::is_published(
-                    &mut index,
-                    pkg.config.registry(),
-                    crate_name,
-                    &version.full_version_string,
-                    pkg.config.certs_source(),
-                ) {
+                if pkg
+                    .published_registries(&version.full_version_string, &mut index)?
+                    .len()
+                    < pkg.config.registries().len()
+                {
                     log::debug!(
                         "enabled {}, v{} is unpublished",
                         crate_name,
@@ -177,6 +175,8 @@ pub fn replace(pkg: &plan::PackageRelease, dry_run: bool) -> Result<(), CliError
         let prev_metadata_var = pkg.initial_version.full_version.build.as_str();
         let version_var = version.bare_version_string.as_str();
         let metadata_var = version.full_version.build.as_str();
+        let branch = git::branch(cwd).ok().flatten();
+        let head_sha = git::head_id(cwd).ok();
         // try replacing text in configured files
         let template = Template {
             prev_version: Some(prev_version_var),
@@ -186,6 +186,14 @@ pub fn replace(pkg: &plan::PackageRelease, dry_run: bool) -> Result<(), CliError
             crate_name: Some(crate_name),
             repository: pkg.meta.repository.as_deref(),
             date: Some(NOW.as_str()),
+            branch: branch.as_deref(),
+            head_sha: head_sha.as_deref(),
+            prev_tag: pkg.prior_tag.as_deref(),
+            registry: Some(
+                pkg.config
+                    .registry()
+                    .unwrap_or(crate::ops::index::CRATES_IO),
+            ),
             tag_name: pkg.planned_tag.as_deref(),
             ..Default::default()
         };
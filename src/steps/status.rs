@@ -0,0 +1,230 @@
+use std::io::Write;
+
+use crate::error::CliError;
+use crate::ops::git;
+use crate::steps::plan;
+
+/// Summarize which packages need a release
+#[derive(Debug, Clone, clap::Args)]
+pub struct StatusStep {
+    #[command(flatten)]
+    manifest: clap_cargo::Manifest,
+
+    #[command(flatten)]
+    workspace: clap_cargo::Workspace,
+
+    /// Custom config file
+    #[arg(short, long = "config", value_name = "PATH")]
+    custom_config: Option<std::path::PathBuf>,
+
+    /// Ignore implicit configuration files.
+    #[arg(long)]
+    isolated: bool,
+
+    /// Unstable options
+    #[arg(short = 'Z', value_name = "FEATURE")]
+    z: Vec<crate::config::UnstableValues>,
+
+    /// Output format
+    #[arg(long, value_enum, default_value_t)]
+    format: StatusFormat,
+}
+
+impl StatusStep {
+    pub fn run(&self) -> Result<(), CliError> {
+        git::git_version()?;
+        let mut index = crate::ops::index::CratesIoIndex::new();
+
+        let ws_meta = self
+            .manifest
+            .metadata()
+            // When evaluating dependency ordering, we need to consider optional dependencies
+            .features(cargo_metadata::CargoOpt::AllFeatures)
+            .exec()?;
+        let config = self.to_config();
+        let pkgs = plan::load(&config, &ws_meta)?;
+        let pkgs = plan::plan(pkgs)?;
+
+        let selected_ids = if self.workspace == clap_cargo::Workspace::default() {
+            // Unlike other steps, default to the whole workspace
+            ws_meta.workspace_members.iter().collect::<Vec<_>>()
+        } else {
+            let (selected_pkgs, _excluded_pkgs) = self.workspace.partition_packages(&ws_meta);
+            selected_pkgs.into_iter().map(|p| &p.id).collect()
+        };
+
+        let statuses = pkgs
+            .values()
+            .filter(|pkg| selected_ids.contains(&&pkg.meta.id))
+            .map(|pkg| PackageStatus::new(&ws_meta, pkg, &mut index))
+            .collect::<Result<Vec<_>, CliError>>()?;
+
+        let output = match self.format {
+            StatusFormat::Table => render_table(&statuses),
+            StatusFormat::Json => {
+                let mut output = serde_json::to_string_pretty(&statuses)?;
+                output.push('\n');
+                output
+            }
+        };
+        std::io::stdout().write_all(output.as_bytes())?;
+
+        Ok(())
+    }
+
+    fn to_config(&self) -> crate::config::ConfigArgs {
+        crate::config::ConfigArgs {
+            custom_config: self.custom_config.clone(),
+            isolated: self.isolated,
+            z: self.z.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum StatusFormat {
+    #[default]
+    Table,
+    Json,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct PackageStatus {
+    pub name: String,
+    pub version: String,
+    /// Latest tag for the package
+    pub tag: Option<String>,
+    /// Commits touching the package since `tag`
+    pub commits: Option<usize>,
+    /// Whether `version` is published, when known
+    pub published: Option<bool>,
+    pub highest_published_version: Option<String>,
+    pub suggested_bump: Option<String>,
+}
+
+impl PackageStatus {
+    pub fn new(
+        ws_meta: &cargo_metadata::Metadata,
+        pkg: &plan::PackageRelease,
+        index: &mut crate::ops::index::CratesIoIndex,
+    ) -> Result<Self, CliError> {
+        let workspace_root = ws_meta.workspace_root.as_std_path();
+        let crate_name = pkg.meta.name.as_str();
+        let version = &pkg.initial_version;
+
+        let mut commits = None;
+        let mut suggested_bump = None;
+        if let Some(prior_tag_name) = pkg.prior_tag.as_deref() {
+            let package_commits =
+                super::changes::package_commits(workspace_root, pkg, prior_tag_name)?;
+            commits = Some(package_commits.len());
+            if let Some(max_status) = super::changes::max_status(&package_commits)
+                && !version.is_prerelease()
+            {
+                let unbumped = pkg
+                    .planned_tag
+                    .as_deref()
+                    .and_then(|t| git::tag_exists(workspace_root, t).ok())
+                    .unwrap_or(false);
+                suggested_bump =
+                    super::changes::suggested_bump(&version.full_version, max_status, !unbumped)
+                        .map(|level| level.to_string());
+            }
+        }
+
+        let (published, highest_published_version) = if pkg.config.publish() {
+            let certs_source = pkg.config.certs_source();
+            // Only published once every registry has it
+            let mut published = Some(true);
+            let mut highest_published_version = None::<semver::Version>;
+            for registry in pkg.config.registries() {
+                let registry_published = index.has_krate_version(
+                    registry,
+                    crate_name,
+                    &version.full_version_string,
+                    certs_source,
+                )?;
+                published = match (published, registry_published) {
+                    (Some(false), _) | (_, Some(false)) => Some(false),
+                    (Some(true), Some(true)) => Some(true),
+                    _ => None,
+                };
+                let registry_highest = index
+                    .highest_krate_version(registry, crate_name, certs_source)?
+                    .and_then(|v| v.parse().ok());
+                highest_published_version = highest_published_version.max(registry_highest);
+            }
+            (published, highest_published_version.map(|v| v.to_string()))
+        } else {
+            (None, None)
+        };
+
+        Ok(Self {
+            name: crate_name.to_owned(),
+            version: version.full_version_string.clone(),
+            tag: pkg.prior_tag.clone(),
+            commits,
+            published,
+            highest_published_version,
+            suggested_bump,
+        })
+    }
+}
+
+fn render_table(statuses: &[PackageStatus]) -> String {
+    const HEADER: [&str; 7] = [
+        "PACKAGE",
+        "VERSION",
+        "TAG",
+        "COMMITS",
+        "PUBLISHED",
+        "LATEST",
+        "BUMP",
+    ];
+
+    let rows = statuses
+        .iter()
+        .map(|s| {
+            [
+                s.name.clone(),
+                s.version.clone(),
+                s.tag.clone().unwrap_or_else(|| "-".to_owned()),
+                s.commits
+                    .map(|c| c.to_string())
+                    .unwrap_or_else(|| "-".to_owned()),
+                match s.published {
+                    Some(true) => "yes".to_owned(),
+                    Some(false) => "no".to_owned(),
+                    None => "-".to_owned(),
+                },
+                s.highest_published_version
+                    .clone()
+                    .unwrap_or_else(|| "-".to_owned()),
+                s.suggested_bump.clone().unwrap_or_else(|| "-".to_owned()),
+            ]
+        })
+        .collect::<Vec<_>>();
+
+    let mut widths = HEADER.map(|h| h.len());
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.len());
+        }
+    }
+
+    let mut output = String::new();
+    let header = HEADER.map(|h| h.to_owned());
+    for row in std::iter::once(&header).chain(&rows) {
+        let line = row
+            .iter()
+            .zip(widths)
+            .map(|(cell, width)| format!("{cell:width$}"))
+            .collect::<Vec<_>>()
+            .join("  ");
+        output.push_str(line.trim_end());
+        output.push('\n');
+    }
+    output
+}
@@ -2,6 +2,7 @@ use std::collections::HashSet;
 
 use crate::error::CliError;
 use crate::ops::git;
+use crate::ops::index;
 use crate::ops::replace::NOW;
 use crate::ops::replace::Template;
 use crate::steps::plan;
@@ -138,7 +139,7 @@ impl TagStep {
         super::confirm("Tag", &selected_pkgs, self.no_confirm, dry_run)?;
 
         // STEP 5: Tag
-        tag(&selected_pkgs, dry_run)?;
+        tag(&ws_meta, &selected_pkgs, dry_run, &mut |_| Ok(()))?;
 
         super::finish(failed, dry_run)
     }
@@ -155,12 +156,19 @@ impl TagStep {
     }
 }
 
-pub fn tag(pkgs: &[plan::PackageRelease], dry_run: bool) -> Result<(), CliError> {
+/// Tag each package, calling `on_tagged` as soon as its tag exists
+pub fn tag(
+    ws_meta: &cargo_metadata::Metadata,
+    pkgs: &[plan::PackageRelease],
+    dry_run: bool,
+    on_tagged: &mut dyn FnMut(&plan::PackageRelease) -> Result<(), CliError>,
+) -> Result<(), CliError> {
     let mut seen_tags = HashSet::new();
     for pkg in pkgs {
-        if let Some(tag_name) = pkg.planned_tag.as_ref()
-            && seen_tags.insert(tag_name)
-        {
+        let Some(tag_name) = pkg.planned_tag.as_ref() else {
+            continue;
+        };
+        if seen_tags.insert(tag_name) {
             let cwd = &pkg.package_root;
             let crate_name = pkg.meta.name.as_str();
 
@@ -169,6 +177,8 @@ pub fn tag(pkgs: &[plan::PackageRelease], dry_run: bool) -> Result<(), CliError>
             let prev_metadata_var = pkg.initial_version.full_version.build.as_str();
             let version_var = version.bare_version_string.as_str();
             let metadata_var = version.full_version.build.as_str();
+            let branch = git::branch(cwd).ok().flatten();
+            let head_sha = git::head_id(cwd).ok();
             let template = Template {
                 prev_version: Some(prev_version_var),
                 prev_metadata: Some(prev_metadata_var),
@@ -176,6 +186,10 @@ pub fn tag(pkgs: &[plan::PackageRelease], dry_run: bool) -> Result<(), CliError>
                 metadata: Some(metadata_var),
                 crate_name: Some(crate_name),
                 date: Some(NOW.as_str()),
+                branch: branch.as_deref(),
+                head_sha: head_sha.as_deref(),
+                prev_tag: pkg.prior_tag.as_deref(),
+                registry: Some(pkg.config.registry().unwrap_or(index::CRATES_IO)),
                 tag_name: Some(tag_name),
                 ..Default::default()
             };
@@ -187,6 +201,9 @@ pub fn tag(pkgs: &[plan::PackageRelease], dry_run: bool) -> Result<(), CliError>
                 return Err(101.into());
             }
         }
+        on_tagged(pkg)?;
+
+        super::hook::run_hook(ws_meta, pkg, super::hook::HookStage::PostTag, dry_run)?;
     }
 
     Ok(())
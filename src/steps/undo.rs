@@ -0,0 +1,218 @@
+use std::collections::HashSet;
+
+use crate::error::CliError;
+use crate::ops::git;
+use crate::steps::journal;
+use crate::steps::plan;
+
+/// Roll back the local changes of an interrupted release
+#[derive(Debug, Clone, clap::Args)]
+pub struct UndoStep {
+    #[command(flatten)]
+    manifest: clap_cargo::Manifest,
+
+    /// Custom config file
+    #[arg(short, long = "config", value_name = "PATH")]
+    custom_config: Option<std::path::PathBuf>,
+
+    /// Ignore implicit configuration files.
+    #[arg(long)]
+    isolated: bool,
+
+    /// Unstable options
+    #[arg(short = 'Z', value_name = "FEATURE")]
+    z: Vec<crate::config::UnstableValues>,
+
+    /// Actually perform a release. Dry-run mode is the default
+    #[arg(short = 'x', long)]
+    execute: bool,
+
+    #[arg(short = 'n', long, conflicts_with = "execute", hide = true)]
+    dry_run: bool,
+
+    /// Skip release confirmation and version preview
+    #[arg(long)]
+    no_confirm: bool,
+}
+
+impl UndoStep {
+    pub fn run(&self) -> Result<(), CliError> {
+        git::git_version()?;
+
+        if self.dry_run {
+            let _ =
+                crate::ops::shell::warn("`--dry-run` is superfluous, dry-run is done by default");
+        }
+
+        let ws_meta = self
+            .manifest
+            .metadata()
+            // When evaluating dependency ordering, we need to consider optional dependencies
+            .features(cargo_metadata::CargoOpt::AllFeatures)
+            .exec()?;
+        let config = self.to_config();
+        let pkgs = plan::load(&config, &ws_meta)?;
+        let pkgs = pkgs.into_values().collect::<Vec<_>>();
+
+        let journal_path = journal::Journal::path(ws_meta.workspace_root.as_std_path())?;
+        let Some(journal) = journal::Journal::load(&journal_path)? else {
+            let _ = crate::ops::shell::error("no interrupted release to undo");
+            return Err(101.into());
+        };
+
+        let dry_run = !self.execute;
+
+        if !dry_run && !self.no_confirm {
+            let confirmed = crate::ops::shell::confirm("Undo the interrupted release?");
+            if !confirmed {
+                return Err(0.into());
+            }
+        }
+
+        rollback(&ws_meta, &journal, &pkgs, dry_run)?;
+
+        super::finish(false, dry_run)
+    }
+
+    fn to_config(&self) -> crate::config::ConfigArgs {
+        crate::config::ConfigArgs {
+            custom_config: self.custom_config.clone(),
+            isolated: self.isolated,
+            z: self.z.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Undo what a release changed locally
+///
+/// This removes the release's commits and tags and restores the files it changed, leaving
+/// anything else in the worktree alone.  This refuses once anything has been published or
+/// pushed.
+pub fn rollback(
+    ws_meta: &cargo_metadata::Metadata,
+    journal: &journal::Journal,
+    pkgs: &[plan::PackageRelease],
+    dry_run: bool,
+) -> Result<(), CliError> {
+    let workspace_root = ws_meta.workspace_root.as_std_path();
+
+    if journal.is_done(&journal::Action::Push) {
+        let _ = crate::ops::shell::error("cannot undo the release, the push already happened");
+        return Err(101.into());
+    }
+
+    // Publishes are journaled as each one finishes
+    let mut published = false;
+    for action in &journal.completed {
+        let journal::Action::Publish { package } = action else {
+            continue;
+        };
+        let version = journal
+            .plan
+            .packages
+            .iter()
+            .find(|p| p.name == *package)
+            .map(|p| p.planned_version.as_deref().unwrap_or(&p.initial_version))
+            .unwrap_or_default();
+        let _ = crate::ops::shell::error(format!(
+            "cannot undo the release, {package} {version} was published"
+        ));
+        published = true;
+    }
+    if published {
+        return Err(101.into());
+    }
+
+    // Fallback for a publish that went through without being journaled, e.g. when interrupted
+    // right after uploading
+    let mut index = crate::ops::index::CratesIoIndex::new();
+    for frozen in &journal.plan.packages {
+        let Some(frozen_index) = frozen.index else {
+            continue;
+        };
+        let Some(pkg) = pkgs.iter().find(|p| p.meta.name.as_str() == frozen.name) else {
+            continue;
+        };
+        let version = frozen
+            .planned_version
+            .as_deref()
+            .unwrap_or(&frozen.initial_version);
+        if !frozen_index.version_published
+            && let Some(registry) = pkg.published_registries(version, &mut index)?.first()
+        {
+            let _ = crate::ops::shell::error(format!(
+                "cannot undo the release, {} {} was published to {}",
+                frozen.name,
+                version,
+                registry.unwrap_or("crates.io")
+            ));
+            return Err(101.into());
+        }
+    }
+
+    let head = journal.plan.head.as_str();
+    let release_commits = journal
+        .completed
+        .iter()
+        .filter(|a| {
+            matches!(
+                a,
+                journal::Action::Commit { .. } | journal::Action::PostRelease { .. }
+            )
+        })
+        .count();
+    if git::commits_since(workspace_root, head)? != Some(release_commits) {
+        let _ = crate::ops::shell::error(format!(
+            "cannot undo the release, HEAD has moved beyond the release commits on top of {head}"
+        ));
+        return Err(101.into());
+    }
+
+    let mut seen_tags = HashSet::new();
+    for action in &journal.completed {
+        let journal::Action::Tag { package } = action else {
+            continue;
+        };
+        let Some(tag_name) = journal
+            .plan
+            .packages
+            .iter()
+            .find(|p| p.name == *package)
+            .and_then(|p| p.planned_tag.as_deref())
+        else {
+            continue;
+        };
+        if seen_tags.insert(tag_name) && git::tag_exists(workspace_root, tag_name)? {
+            let _ = crate::ops::shell::status("Deleting", format!("tag {tag_name}"));
+            if !git::delete_tag(workspace_root, tag_name, dry_run)? {
+                return Err(101.into());
+            }
+        }
+    }
+
+    // Only touch what the release changed, whether committed or not
+    let changed = git::changed_since(workspace_root, head)?;
+    let _ = crate::ops::shell::status("Resetting", format!("to {head}"));
+    if !git::reset_mixed(workspace_root, head, dry_run)? {
+        return Err(101.into());
+    }
+    let (added, modified): (Vec<_>, Vec<_>) = changed.into_iter().partition(|(_, added)| *added);
+    let modified = modified
+        .into_iter()
+        .map(|(path, _)| path)
+        .collect::<Vec<_>>();
+    if !modified.is_empty() && !git::checkout_paths(workspace_root, head, &modified, dry_run)? {
+        return Err(101.into());
+    }
+    for (path, _) in added {
+        log::debug!("removing {}", path.display());
+        if !dry_run {
+            std::fs::remove_file(&path)?;
+        }
+    }
+
+    journal.finish(dry_run)?;
+
+    Ok(())
+}
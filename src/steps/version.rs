@@ -78,7 +78,7 @@ impl VersionStep {
                 pkg.set_prior_tag(prev_tag.to_owned());
             }
             if pkg.config.release() {
-                pkg.bump(&self.level_or_version, self.metadata.as_deref())?;
+                pkg.bump(&ws_meta, &self.level_or_version, self.metadata.as_deref())?;
             }
         }
 
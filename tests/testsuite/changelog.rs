@@ -0,0 +1,159 @@
+use cargo_test_support::cargo_test;
+use cargo_test_support::project;
+use snapbox::str;
+
+use crate::CargoCommand;
+use crate::create_default_gitconfig;
+use crate::init_registry;
+
+#[cargo_test]
+fn generate_changelog() {
+    init_registry();
+    create_default_gitconfig();
+    let project = project()
+        .file(".gitignore", "/target\n")
+        .file(
+            "Cargo.toml",
+            r#"
+            [package]
+            name = "sample"
+            version = "0.1.0"
+            edition = "2024"
+            "#,
+        )
+        .file(
+            "release.toml",
+            r#"
+            publish = false
+            push = false
+            changelog = "CHANGELOG.md"
+            "#,
+        )
+        .file(
+            "CHANGELOG.md",
+            "# Changelog\n\n## [0.1.0] - 2020-01-01\n\n- Initial release\n",
+        )
+        .file("src/lib.rs", "pub fn sample() {}\n")
+        .build();
+    project.process("cargo").arg("generate-lockfile").run();
+    let repo = cargo_test_support::git::init(&project.root());
+    cargo_test_support::git::add(&repo);
+    cargo_test_support::git::commit(&repo);
+    cargo_test_support::git::tag(&repo, "v0.1.0");
+    project.change_file("src/lib.rs", "pub fn sample() {}\npub fn feature() {}\n");
+    project
+        .process("git")
+        .args(&["commit", "-am", "feat: Add a feature"])
+        .run();
+    project.change_file("src/lib.rs", "pub fn sample() {}\npub fn feature() {}\n\n");
+    project
+        .process("git")
+        .args(&["commit", "-am", "style: Add a blank line"])
+        .run();
+
+    snapbox::cmd::Command::cargo_ui()
+        .arg("release")
+        .args(["minor", "--no-confirm"])
+        .current_dir(project.root())
+        .assert()
+        .success()
+        .stdout_eq(str![])
+        .stderr_eq(str![[r#"
+   Upgrading sample from 0.1.0 to 0.2.0
+    Updating CHANGELOG.md
+--- CHANGELOG.md	original
++++ CHANGELOG.md	updated
+@@ -1,5 +1,11 @@
+ # Changelog
+ 
++## [0.2.0] - [..]
++
++### Added
++
++- Add a feature
++
+ ## [0.1.0] - 2020-01-01
+ 
+ - Initial release
+
+warning: aborting release due to dry run; re-run with `--execute`
+
+"#]]);
+
+    snapbox::cmd::Command::cargo_ui()
+        .arg("release")
+        .args(["minor", "--execute", "--no-confirm"])
+        .current_dir(project.root())
+        .assert()
+        .success();
+
+    let changelog = std::fs::read_to_string(project.root().join("CHANGELOG.md")).unwrap();
+    assert!(changelog.contains("## [0.2.0] - "), "{changelog}");
+    assert!(
+        changelog.contains("### Added\n\n- Add a feature\n\n## [0.1.0]"),
+        "{changelog}"
+    );
+    assert!(!changelog.contains("blank line"), "{changelog}");
+}
+
+#[cargo_test]
+fn create_changelog() {
+    init_registry();
+    create_default_gitconfig();
+    let project = project()
+        .file(".gitignore", "/target\n")
+        .file(
+            "Cargo.toml",
+            r#"
+            [package]
+            name = "sample"
+            version = "0.1.0"
+            edition = "2024"
+            "#,
+        )
+        .file(
+            "release.toml",
+            r#"
+            publish = false
+            push = false
+            changelog = "CHANGELOG.md"
+            "#,
+        )
+        .file("src/lib.rs", "pub fn sample() {}\n")
+        .build();
+    project.process("cargo").arg("generate-lockfile").run();
+    let repo = cargo_test_support::git::init(&project.root());
+    cargo_test_support::git::add(&repo);
+    cargo_test_support::git::commit(&repo);
+    cargo_test_support::git::tag(&repo, "v0.1.0");
+    project.change_file("src/lib.rs", "pub fn sample() {}\npub fn feature() {}\n");
+    project
+        .process("git")
+        .args(&["commit", "-am", "feat: Add a feature"])
+        .run();
+
+    snapbox::cmd::Command::cargo_ui()
+        .arg("release")
+        .args(["minor", "--execute", "--no-confirm"])
+        .current_dir(project.root())
+        .assert()
+        .success()
+        .stdout_eq(str![[r#"
+[master [..]] chore: Release sample version 0.2.0
+ 3 files changed, 14 insertions(+), 2 deletions(-)
+ create mode 100644 CHANGELOG.md
+
+"#]])
+        .stderr_eq(str![[r#"
+   Upgrading sample from 0.1.0 to 0.2.0
+
+"#]]);
+
+    let repo = git2::Repository::open(project.root()).unwrap();
+    let tree = repo.head().unwrap().peel_to_tree().unwrap();
+    assert!(tree.get_name("CHANGELOG.md").is_some());
+    let status = repo
+        .status_file(std::path::Path::new("CHANGELOG.md"))
+        .unwrap();
+    assert_eq!(status, git2::Status::CURRENT);
+}
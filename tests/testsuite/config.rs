@@ -0,0 +1,713 @@
+use cargo_test_support::cargo_test;
+use cargo_test_support::project;
+use snapbox::str;
+
+use crate::CargoCommand;
+use crate::create_default_gitconfig;
+use crate::init_registry;
+
+fn sample_workspace(release_toml: &str) -> cargo_test_support::Project {
+    create_default_gitconfig();
+    let project = project()
+        .file(".gitignore", "/target\n")
+        .file(
+            "Cargo.toml",
+            r#"
+            [workspace]
+            members = ["a", "b"]
+            resolver = "3"
+            "#,
+        )
+        .file(
+            "a/Cargo.toml",
+            r#"
+            [package]
+            name = "a"
+            version = "0.1.0"
+            edition = "2024"
+            publish = false
+            "#,
+        )
+        .file("a/src/lib.rs", "pub fn a() {}\n")
+        .file(
+            "b/Cargo.toml",
+            r#"
+            [package]
+            name = "b"
+            version = "0.2.0"
+            edition = "2024"
+            publish = false
+            "#,
+        )
+        .file("b/src/lib.rs", "pub fn b() {}\n")
+        .file("release.toml", release_toml)
+        .build();
+    project.process("cargo").arg("generate-lockfile").run();
+    let repo = cargo_test_support::git::init(&project.root());
+    cargo_test_support::git::add(&repo);
+    cargo_test_support::git::commit(&repo);
+    project
+}
+
+#[cargo_test]
+fn unknown_placeholder() {
+    init_registry();
+    let project = sample_workspace(
+        r#"
+tag-message = "Release {{crate-name}} {{version}}"
+"#,
+    );
+
+    snapbox::cmd::Command::cargo_ui()
+        .arg("release")
+        .args(["patch", "--workspace"])
+        .current_dir(project.root())
+        .assert()
+        .failure()
+        .stderr_eq(str![[r#"
+error: invalid template in `tag-message`
+  ╭▸ [ROOT]/foo/release.toml:2:26
+  │
+2 │ tag-message = "Release {{crate-name}} {{version}}"
+  │                          ━━━━━━━━━━ unknown placeholder `crate-name`
+  ╰╴
+help: available placeholders: `{{prev_version}}`, `{{prev_metadata}}`, `{{version}}`, `{{metadata}}`, `{{crate_name}}`, `{{date}}`, `{{tag_name}}`, `{{branch}}`, `{{head_sha}}`, `{{prev_tag}}`, `{{registry}}`
+
+"#]]);
+}
+
+#[cargo_test]
+fn unavailable_placeholder() {
+    init_registry();
+    let project = sample_workspace(
+        r#"
+consolidate-commits = true
+pre-release-commit-message = "Release {{crate_name}}"
+"#,
+    );
+
+    snapbox::cmd::Command::cargo_ui()
+        .arg("release")
+        .args(["patch", "--workspace"])
+        .current_dir(project.root())
+        .assert()
+        .failure()
+        .stderr_eq(str![[r#"
+error: invalid template in `pre-release-commit-message`
+  ╭▸ [ROOT]/foo/release.toml:3:41
+  │
+3 │ pre-release-commit-message = "Release {{crate_name}}"
+  │                                         ━━━━━━━━━━ `crate_name` is not available here
+  ╰╴
+help: a consolidated commit covers all crates; available placeholders: `{{version}}`, `{{metadata}}`, `{{date}}`, `{{branch}}`, `{{head_sha}}`
+
+"#]]);
+}
+
+#[cargo_test]
+fn overridden_placeholder() {
+    init_registry();
+    let project = sample_workspace(
+        r#"
+tag-name = "{{prefix}}{{version}}"
+"#,
+    );
+
+    snapbox::cmd::Command::cargo_ui()
+        .arg("release")
+        .args(["patch", "--workspace", "--tag-name", "{{version}}-{{crate}}"])
+        .current_dir(project.root())
+        .assert()
+        .failure()
+        .stderr_eq(str![[r#"
+error: invalid template in `tag-name`: unknown placeholder `crate` in `{{version}}-{{crate}}`
+       available placeholders: `{{prev_version}}`, `{{prev_metadata}}`, `{{version}}`, `{{metadata}}`, `{{crate_name}}`, `{{prefix}}`
+
+"#]]);
+}
+
+#[cargo_test]
+fn available_placeholders_render() {
+    // Every placeholder each field is validated against must be set where it is rendered
+    init_registry();
+    const ALL: &str = "prev_version={{prev_version}} prev_metadata={{prev_metadata}} \
+version={{version}} metadata={{metadata}} crate_name={{crate_name}} date={{date}} \
+branch={{branch}} head_sha={{head_sha}} prev_tag={{prev_tag}} registry={{registry}}";
+    let project = sample_workspace(&format!(
+        r#"
+consolidate-commits = false
+tag-message = "{ALL} tag_name={{{{tag_name}}}}"
+pre-release-commit-message = "{ALL}"
+pre-release-hook = ["sh", "-c", "echo \"$0\" >> ../hook.log", "{ALL} tag_name={{{{tag_name}}}}"]
+pre-release-replacements = [
+  {{file="src/lib.rs", search="// unreleased", replace="// {ALL} repository={{{{repository}}}} tag_name={{{{tag_name}}}}"}},
+]
+"#
+    ));
+    project.change_file(
+        "a/Cargo.toml",
+        r#"
+        [package]
+        name = "a"
+        version = "0.1.0"
+        edition = "2024"
+        publish = false
+        repository = "https://example.com/a"
+        "#,
+    );
+    project.change_file("a/src/lib.rs", "pub fn a() {}\n// unreleased\n");
+    let repo = git2::Repository::open(project.root()).unwrap();
+    cargo_test_support::git::add(&repo);
+    cargo_test_support::git::commit(&repo);
+    cargo_test_support::git::tag(&repo, "a-v0.1.0");
+
+    snapbox::cmd::Command::cargo_ui()
+        .arg("release")
+        .args(["patch", "-p", "a", "--no-publish", "--no-push"])
+        .args(["--execute", "--no-confirm"])
+        .current_dir(project.root())
+        .assert()
+        .success();
+
+    let hook = std::fs::read_to_string(project.root().join("hook.log")).unwrap();
+    snapbox::assert_data_eq!(
+        hook,
+        str![[r#"
+prev_version=0.1.0 prev_metadata= version=0.1.1 metadata= crate_name=a date=[..] branch=master head_sha=[..] prev_tag=a-v0.1.0 registry=crates-io tag_name=a-v0.1.1
+
+"#]]
+    );
+    let lib = std::fs::read_to_string(project.root().join("a/src/lib.rs")).unwrap();
+    snapbox::assert_data_eq!(
+        lib,
+        str![[r#"
+pub fn a() {}
+// prev_version=0.1.0 prev_metadata= version=0.1.1 metadata= crate_name=a date=[..] branch=master head_sha=[..] prev_tag=a-v0.1.0 registry=crates-io repository=https://example.com/a tag_name=a-v0.1.1
+
+"#]]
+    );
+    let head = repo.head().unwrap().peel_to_commit().unwrap();
+    snapbox::assert_data_eq!(
+        head.message().unwrap(),
+        str![[r#"
+prev_version=0.1.0 prev_metadata= version=0.1.1 metadata= crate_name=a date=[..] branch=master head_sha=[..] prev_tag=a-v0.1.0 registry=crates-io
+
+"#]]
+    );
+    let tag = repo
+        .revparse_single("a-v0.1.1")
+        .unwrap()
+        .peel_to_tag()
+        .unwrap();
+    snapbox::assert_data_eq!(
+        tag.message().unwrap(),
+        str![[r#"
+prev_version=0.1.0 prev_metadata= version=0.1.1 metadata= crate_name=a date=[..] branch=master head_sha=[..] prev_tag=a-v0.1.0 registry=crates-io tag_name=a-v0.1.1
+
+"#]]
+    );
+}
+
+#[cargo_test]
+fn explain() {
+    init_registry();
+    let project = sample_workspace(
+        r#"
+push = false
+sign-commit = true
+"#,
+    );
+    project.change_file(
+        "a/Cargo.toml",
+        r#"
+[package]
+name = "a"
+version = "0.1.0"
+edition = "2024"
+publish = false
+
+[package.metadata.release]
+push = true
+"#,
+    );
+
+    snapbox::cmd::Command::cargo_ui()
+        .arg("release")
+        .args(["config", "--explain", "-p", "a", "--no-sign-commit"])
+        .current_dir(project.root())
+        .assert()
+        .success()
+        .stdout_eq(str![[r#"
+allow-branch = ["*", "!HEAD"] # default
+certs-source = "webpki" # default
+consolidate-commits = false # default
+dependent-version = "upgrade" # default
+enable-all-features = false # default
+enable-features = [] # default
+metadata = "optional" # default
+owners = [] # default
+post-release-commit-message = "chore: Start {{crate_name}} {{version}} development" # default
+pre-release-commit-message = "chore: Release {{crate_name}} version {{version}}" # default
+pre-release-replacements = [] # default
+publish = false # [ROOT]/foo/a/Cargo.toml (implied by package fields)
+#   overrides true from default
+publish-jobs = 1 # default
+publish-retries = 0 # default
+publish-timeout = 300 # default
+publish-wait = "cargo" # default
+push = true # [ROOT]/foo/a/Cargo.toml:9
+#   overrides false from [ROOT]/foo/release.toml:2
+#   overrides true from default
+push-options = [] # default
+push-remote = "origin" # default
+rate-limit.existing-packages = 30 # default
+rate-limit.new-packages = 5 # default
+rate-limit.throttle = false # default
+release = true # default
+sign-commit = false # CLI
+#   overrides true from [ROOT]/foo/release.toml:3
+#   overrides false from default
+sign-tag = false # default
+tag = true # default
+tag-message = "chore: Release {{crate_name}} version {{version}}" # default
+tag-name = "{{prefix}}v{{version}}" # default
+unstable.workspace-publish = false # default
+verify = true # default
+
+"#]]);
+}
+
+#[cargo_test]
+fn explain_publish_field() {
+    init_registry();
+    create_default_gitconfig();
+    let project = project()
+        .file(".gitignore", "/target\n")
+        .file(
+            "Cargo.toml",
+            r#"
+            [package]
+            name = "sample"
+            version = "0.1.0"
+            edition = "2024"
+            publish = ["alternative"]
+            "#,
+        )
+        .file("src/lib.rs", "pub fn sample() {}\n")
+        .build();
+    project.process("cargo").arg("generate-lockfile").run();
+    let repo = cargo_test_support::git::init(&project.root());
+    cargo_test_support::git::add(&repo);
+    cargo_test_support::git::commit(&repo);
+
+    snapbox::cmd::Command::cargo_ui()
+        .arg("release")
+        .args(["config", "--explain"])
+        .current_dir(project.root())
+        .assert()
+        .success()
+        .stdout_eq(str![[r#"
+allow-branch = ["*", "!HEAD"] # default
+certs-source = "webpki" # default
+consolidate-commits = false # default
+dependent-version = "upgrade" # default
+enable-all-features = false # default
+enable-features = [] # default
+metadata = "optional" # default
+owners = [] # default
+post-release-commit-message = "chore: Start {{crate_name}} {{version}} development" # default
+pre-release-commit-message = "chore: Release {{crate_name}} version {{version}}" # default
+pre-release-replacements = [] # default
+publish = true # default
+publish-jobs = 1 # default
+publish-retries = 0 # default
+publish-timeout = 300 # default
+publish-wait = "cargo" # default
+push = true # default
+push-options = [] # default
+push-remote = "origin" # default
+rate-limit.existing-packages = 30 # default
+rate-limit.new-packages = 5 # default
+rate-limit.throttle = false # default
+registry = "alternative" # [ROOT]/foo/Cargo.toml (package.publish)
+release = true # default
+sign-commit = false # default
+sign-tag = false # default
+tag = true # default
+tag-message = "chore: Release {{crate_name}} version {{version}}" # default
+tag-name = "{{prefix}}v{{version}}" # default
+unstable.workspace-publish = false # default
+verify = true # default
+
+"#]]);
+}
+
+#[cargo_test]
+fn profile() {
+    init_registry();
+    let project = sample_workspace(
+        r#"
+push = true
+tag-name = "{{prefix}}v{{version}}"
+
+[profile.nightly]
+push = false
+tag-name = "nightly-{{version}}"
+"#,
+    );
+    project.change_file(
+        "a/release.toml",
+        r#"
+tag-name = "a-v{{version}}"
+
+[profile.nightly]
+sign-tag = true
+"#,
+    );
+
+    snapbox::cmd::Command::cargo_ui()
+        .arg("release")
+        .args(["config", "--explain", "-p", "a", "--profile", "nightly"])
+        .current_dir(project.root())
+        .assert()
+        .success()
+        .stdout_eq(str![[r#"
+allow-branch = ["*", "!HEAD"] # default
+certs-source = "webpki" # default
+consolidate-commits = false # default
+dependent-version = "upgrade" # default
+enable-all-features = false # default
+enable-features = [] # default
+metadata = "optional" # default
+owners = [] # default
+post-release-commit-message = "chore: Start {{crate_name}} {{version}} development" # default
+pre-release-commit-message = "chore: Release {{crate_name}} version {{version}}" # default
+pre-release-replacements = [] # default
+publish = false # [ROOT]/foo/a/Cargo.toml (implied by package fields)
+#   overrides true from default
+publish-jobs = 1 # default
+publish-retries = 0 # default
+publish-timeout = 300 # default
+publish-wait = "cargo" # default
+push = false # [ROOT]/foo/release.toml:6 (profile `nightly`)
+#   overrides true from [ROOT]/foo/release.toml:2
+#   overrides true from default
+push-options = [] # default
+push-remote = "origin" # default
+rate-limit.existing-packages = 30 # default
+rate-limit.new-packages = 5 # default
+rate-limit.throttle = false # default
+release = true # default
+sign-commit = false # default
+sign-tag = true # [ROOT]/foo/a/release.toml:5 (profile `nightly`)
+#   overrides false from default
+tag = true # default
+tag-message = "chore: Release {{crate_name}} version {{version}}" # default
+tag-name = "nightly-{{version}}" # [ROOT]/foo/release.toml:7 (profile `nightly`)
+#   overrides "a-v{{version}}" from [ROOT]/foo/a/release.toml:2
+#   overrides "{{prefix}}v{{version}}" from [ROOT]/foo/release.toml:3
+#   overrides "{{prefix}}v{{version}}" from default
+unstable.workspace-publish = false # default
+verify = true # default
+
+"#]]);
+}
+
+#[cargo_test]
+fn undefined_profile() {
+    init_registry();
+    let project = sample_workspace("");
+
+    snapbox::cmd::Command::cargo_ui()
+        .arg("release")
+        .args(["patch", "--workspace", "--profile", "nightly"])
+        .current_dir(project.root())
+        .assert()
+        .failure()
+        .stderr_eq(str![[r#"
+error: profile `nightly` is not defined in any release config
+
+"#]]);
+}
+
+#[cargo_test]
+fn extends() {
+    init_registry();
+    let base = cargo_test_support::paths::root().join("org/release.toml");
+    std::fs::create_dir_all(base.parent().unwrap()).unwrap();
+    std::fs::write(
+        &base,
+        r#"
+push = false
+sign-tag = true
+"#,
+    )
+    .unwrap();
+    let project = sample_workspace(
+        r#"
+extends = ["../org/release.toml"]
+sign-tag = false
+"#,
+    );
+
+    snapbox::cmd::Command::cargo_ui()
+        .arg("release")
+        .args(["config", "--explain"])
+        .current_dir(project.root())
+        .assert()
+        .success()
+        .stdout_eq(str![[r#"
+allow-branch = ["*", "!HEAD"] # default
+certs-source = "webpki" # default
+consolidate-commits = false # default
+dependent-version = "upgrade" # default
+enable-all-features = false # default
+enable-features = [] # default
+metadata = "optional" # default
+owners = [] # default
+post-release-commit-message = "chore: Start {{crate_name}} {{version}} development" # default
+pre-release-commit-message = "chore: Release {{crate_name}} version {{version}}" # default
+pre-release-replacements = [] # default
+publish = true # default
+publish-jobs = 1 # default
+publish-retries = 0 # default
+publish-timeout = 300 # default
+publish-wait = "cargo" # default
+push = false # [ROOT]/org/release.toml:2
+#   overrides true from default
+push-options = [] # default
+push-remote = "origin" # default
+rate-limit.existing-packages = 30 # default
+rate-limit.new-packages = 5 # default
+rate-limit.throttle = false # default
+release = true # default
+sign-commit = false # default
+sign-tag = false # [ROOT]/foo/release.toml:3
+#   overrides true from [ROOT]/org/release.toml:3
+#   overrides false from default
+tag = true # default
+tag-message = "chore: Release {{crate_name}} version {{version}}" # default
+tag-name = "{{prefix}}v{{version}}" # default
+unstable.workspace-publish = false # default
+verify = true # default
+
+"#]]);
+}
+
+#[cargo_test]
+fn extends_pin() {
+    init_registry();
+    let base = cargo_test_support::paths::root().join("org/release.toml");
+    std::fs::create_dir_all(base.parent().unwrap()).unwrap();
+    std::fs::write(&base, "push = false\n").unwrap();
+    let project = sample_workspace(
+        r#"
+extends = ["../org/release.toml"]
+"#,
+    );
+
+    snapbox::cmd::Command::cargo_ui()
+        .arg("release")
+        .args(["config", "--pin"])
+        .current_dir(project.root())
+        .assert()
+        .success()
+        .stderr_eq(str![[r#"
+      Pinned 1 inherited config(s) in release.lock
+
+"#]]);
+    snapbox::assert_data_eq!(
+        std::fs::read_to_string(project.root().join("release.lock")).unwrap(),
+        str![[r##"
+# Pins the configs inherited through `extends`, see `cargo release config --pin`
+[extends]
+"../org/release.toml" = "98857aeddad1ee063ca7bfb3b7bc7af1a265c1d0"
+
+"##]]
+    );
+
+    std::fs::write(&base, "push = true\n").unwrap();
+    snapbox::cmd::Command::cargo_ui()
+        .arg("release")
+        .args(["patch", "--workspace"])
+        .current_dir(project.root())
+        .assert()
+        .failure()
+        .stderr_eq(str![[r#"
+error: `../org/release.toml` changed since it was pinned in `release.lock`; review it and run `cargo release config --pin` to accept the change
+
+"#]]);
+}
+
+#[cargo_test]
+fn env() {
+    init_registry();
+    let project = sample_workspace(
+        r#"
+push = true
+"#,
+    );
+
+    snapbox::cmd::Command::cargo_ui()
+        .arg("release")
+        .args(["config", "--explain", "--no-sign-commit"])
+        .env("CARGO_RELEASE_PUSH", "false")
+        .env("CARGO_RELEASE_SIGN_COMMIT", "true")
+        .env("CARGO_RELEASE_ALLOW_BRANCH", "main,release/*")
+        .env("CARGO_RELEASE_RATE_LIMIT_NEW_PACKAGES", "10")
+        .current_dir(project.root())
+        .assert()
+        .success()
+        .stdout_eq(str![[r#"
+allow-branch = ["main", "release/*"] # environment variable `CARGO_RELEASE_ALLOW_BRANCH`
+#   overrides ["*", "!HEAD"] from default
+certs-source = "webpki" # default
+consolidate-commits = false # default
+dependent-version = "upgrade" # default
+enable-all-features = false # default
+enable-features = [] # default
+metadata = "optional" # default
+owners = [] # default
+post-release-commit-message = "chore: Start {{crate_name}} {{version}} development" # default
+pre-release-commit-message = "chore: Release {{crate_name}} version {{version}}" # default
+pre-release-replacements = [] # default
+publish = true # default
+publish-jobs = 1 # default
+publish-retries = 0 # default
+publish-timeout = 300 # default
+publish-wait = "cargo" # default
+push = false # environment variable `CARGO_RELEASE_PUSH`
+#   overrides true from [ROOT]/foo/release.toml:2
+#   overrides true from default
+push-options = [] # default
+push-remote = "origin" # default
+rate-limit.existing-packages = 30 # default
+rate-limit.new-packages = 10 # environment variable `CARGO_RELEASE_RATE_LIMIT_NEW_PACKAGES`
+#   overrides 5 from default
+rate-limit.throttle = false # default
+release = true # default
+sign-commit = false # CLI
+#   overrides true from environment variable `CARGO_RELEASE_SIGN_COMMIT`
+#   overrides false from default
+sign-tag = false # default
+tag = true # default
+tag-message = "chore: Release {{crate_name}} version {{version}}" # default
+tag-name = "{{prefix}}v{{version}}" # default
+unstable.workspace-publish = false # default
+verify = true # default
+
+"#]]);
+}
+
+#[cargo_test]
+fn invalid_env() {
+    init_registry();
+    let project = sample_workspace("");
+
+    snapbox::cmd::Command::cargo_ui()
+        .arg("release")
+        .args(["config"])
+        .env("CARGO_RELEASE_PUSH", "sometimes")
+        .current_dir(project.root())
+        .assert()
+        .failure()
+        .stderr_eq(str![[r#"
+error: invalid `CARGO_RELEASE_PUSH`: invalid type: string "sometimes", expected a boolean
+
+"#]]);
+}
+
+#[cargo_test]
+fn package_tables() {
+    init_registry();
+    let project = sample_workspace(
+        r#"
+tag-prefix = "{{crate_name}}-"
+
+[package."*"]
+sign-tag = true
+
+[package.a]
+tag-prefix = "alpha-"
+sign-tag = false
+"#,
+    );
+    project.change_file(
+        "a/release.toml",
+        r#"
+sign-commit = true
+tag-prefix = "a/"
+"#,
+    );
+
+    snapbox::cmd::Command::cargo_ui()
+        .arg("release")
+        .args(["config", "--explain", "-p", "a"])
+        .current_dir(project.root())
+        .assert()
+        .success()
+        .stdout_eq(str![[r#"
+allow-branch = ["*", "!HEAD"] # default
+certs-source = "webpki" # default
+consolidate-commits = false # default
+dependent-version = "upgrade" # default
+enable-all-features = false # default
+enable-features = [] # default
+metadata = "optional" # default
+owners = [] # default
+post-release-commit-message = "chore: Start {{crate_name}} {{version}} development" # default
+pre-release-commit-message = "chore: Release {{crate_name}} version {{version}}" # default
+pre-release-replacements = [] # default
+publish = false # [ROOT]/foo/a/Cargo.toml (implied by package fields)
+#   overrides true from default
+publish-jobs = 1 # default
+publish-retries = 0 # default
+publish-timeout = 300 # default
+publish-wait = "cargo" # default
+push = true # default
+push-options = [] # default
+push-remote = "origin" # default
+rate-limit.existing-packages = 30 # default
+rate-limit.new-packages = 5 # default
+rate-limit.throttle = false # default
+release = true # default
+sign-commit = true # [ROOT]/foo/a/release.toml:2
+#   overrides false from default
+sign-tag = false # [ROOT]/foo/release.toml:9 (package `a`)
+#   overrides true from [ROOT]/foo/release.toml:5 (package `*`)
+#   overrides false from default
+tag = true # default
+tag-message = "chore: Release {{crate_name}} version {{version}}" # default
+tag-name = "{{prefix}}v{{version}}" # default
+tag-prefix = "a/" # [ROOT]/foo/a/release.toml:3
+#   overrides "alpha-" from [ROOT]/foo/release.toml:8 (package `a`)
+#   overrides "{{crate_name}}-" from [ROOT]/foo/release.toml:2
+unstable.workspace-publish = false # default
+verify = true # default
+
+"#]]);
+}
+
+#[cargo_test]
+fn package_tables_in_package() {
+    init_registry();
+    let project = sample_workspace("");
+    project.change_file(
+        "a/release.toml",
+        r#"
+[package.b]
+publish = false
+"#,
+    );
+
+    snapbox::cmd::Command::cargo_ui()
+        .arg("release")
+        .args(["config", "-p", "a"])
+        .current_dir(project.root())
+        .assert()
+        .failure()
+        .stderr_eq(str![[r#"
+error: `package` tables are only supported in workspace config, found in `[ROOT]/foo/a/release.toml`
+
+"#]]);
+}
@@ -0,0 +1,220 @@
+use cargo_test_support::cargo_test;
+use cargo_test_support::project;
+use snapbox::str;
+
+use crate::CargoCommand;
+use crate::create_default_gitconfig;
+use crate::init_registry;
+
+fn sample_project(release_toml: &str) -> cargo_test_support::Project {
+    create_default_gitconfig();
+    let project = project()
+        .file(".gitignore", "/target\n")
+        .file(
+            "Cargo.toml",
+            r#"
+            [package]
+            name = "sample"
+            version = "0.1.0"
+            edition = "2024"
+            "#,
+        )
+        .file("release.toml", release_toml)
+        .file("src/lib.rs", "pub fn sample() {}")
+        .build();
+    project.process("cargo").arg("generate-lockfile").run();
+    let repo = cargo_test_support::git::init(&project.root());
+    cargo_test_support::git::add(&repo);
+    cargo_test_support::git::commit(&repo);
+    git2::Repository::init_bare(cargo_test_support::paths::root().join("remote.git")).unwrap();
+    project
+        .process("git")
+        .args(&["remote", "add", "origin", "../remote.git"])
+        .run();
+    project
+        .process("git")
+        .args(&["push", "origin", "master"])
+        .run();
+    project
+}
+
+fn sample_workspace(release_toml: &str) -> cargo_test_support::Project {
+    create_default_gitconfig();
+    let project = project()
+        .file(".gitignore", "/target\n")
+        .file(
+            "Cargo.toml",
+            r#"
+            [workspace]
+            members = ["a", "b"]
+            resolver = "3"
+            "#,
+        )
+        .file(
+            "a/Cargo.toml",
+            r#"
+            [package]
+            name = "a"
+            version = "0.1.0"
+            edition = "2024"
+            publish = false
+            "#,
+        )
+        .file("a/src/lib.rs", "pub fn a() {}\n")
+        .file(
+            "b/Cargo.toml",
+            r#"
+            [package]
+            name = "b"
+            version = "0.2.0"
+            edition = "2024"
+            publish = false
+            "#,
+        )
+        .file("b/src/lib.rs", "pub fn b() {}\n")
+        .file("release.toml", release_toml)
+        .build();
+    project.process("cargo").arg("generate-lockfile").run();
+    let repo = cargo_test_support::git::init(&project.root());
+    cargo_test_support::git::add(&repo);
+    cargo_test_support::git::commit(&repo);
+    project
+}
+
+#[cargo_test]
+fn hooks_run_at_each_stage() {
+    init_registry();
+    let log =
+        r#"echo "$HOOK_STAGE $CRATE_NAME $NEW_VERSION [$TAG_NAME] [$PUSH_REMOTE]" >> ../hooks.log"#;
+    let project = sample_project(&format!(
+        r#"
+        publish = false
+        pre-release-hook = ["sh", "-c", '{log}']
+        post-commit-hook = ["sh", "-c", 'test -n "$COMMIT_ID" && {log}']
+        post-tag-hook = ["sh", "-c", '{log}']
+        post-push-hook = ["sh", "-c", '{log}']
+        "#
+    ));
+
+    snapbox::cmd::Command::cargo_ui()
+        .arg("release")
+        .args(["patch", "--execute", "--no-confirm"])
+        .current_dir(project.root())
+        .assert()
+        .success();
+
+    let hooks =
+        std::fs::read_to_string(cargo_test_support::paths::root().join("hooks.log")).unwrap();
+    snapbox::assert_data_eq!(
+        hooks,
+        str![[r#"
+pre-release sample 0.1.1 [] []
+post-commit sample 0.1.1 [] []
+post-tag sample 0.1.1 [v0.1.1] []
+post-push sample 0.1.1 [v0.1.1] [origin]
+
+"#]]
+    );
+}
+
+#[cargo_test]
+fn failing_post_commit_hook_aborts() {
+    init_registry();
+    let project = sample_project(
+        r#"
+        publish = false
+        post-commit-hook = ["sh", "-c", "exit 1"]
+        "#,
+    );
+
+    snapbox::cmd::Command::cargo_ui()
+        .arg("release")
+        .args(["patch", "--execute", "--no-confirm"])
+        .current_dir(project.root())
+        .assert()
+        .failure()
+        .stderr_eq(str![[r#"
+   Upgrading sample from 0.1.0 to 0.1.1
+error: release of sample aborted by non-zero return of post-commit hook.
+Rolling back release
+   Resetting to [..]
+
+"#]]);
+
+    let repo = git2::Repository::open(project.root()).unwrap();
+    assert!(repo.find_reference("refs/tags/v0.1.1").is_err());
+}
+
+#[cargo_test]
+fn workspace_hook_runs_once() {
+    init_registry();
+    let project = sample_workspace(
+        r#"
+        push = false
+        pre-release-hook = ["sh", "-c", 'echo "$CRATE_NAME" >> "$WORKSPACE_ROOT/../hooks.log"']
+        workspace-pre-release-hook = ["sh", "-c", 'cat >> ../hooks.log && echo >> ../hooks.log && echo "$RELEASE_PACKAGES" >> ../hooks.log']
+        "#,
+    );
+
+    snapbox::cmd::Command::cargo_ui()
+        .arg("release")
+        .args(["patch", "--workspace", "--no-confirm"])
+        .current_dir(project.root())
+        .assert()
+        .success();
+
+    let hooks =
+        std::fs::read_to_string(cargo_test_support::paths::root().join("hooks.log")).unwrap();
+    snapbox::assert_data_eq!(
+        hooks,
+        str![[r#"
+a
+b
+[{"name":"a","prev-version":"0.1.0","prev-metadata":"","new-version":"0.1.1","new-metadata":"","crate-root":"[..]/foo/a"},{"name":"b","prev-version":"0.2.0","prev-metadata":"","new-version":"0.2.1","new-metadata":"","crate-root":"[..]/foo/b"}]
+[{"name":"a","prev-version":"0.1.0","prev-metadata":"","new-version":"0.1.1","new-metadata":"","crate-root":"[..]/foo/a"},{"name":"b","prev-version":"0.2.0","prev-metadata":"","new-version":"0.2.1","new-metadata":"","crate-root":"[..]/foo/b"}]
+
+"#]]
+    );
+}
+
+#[cargo_test]
+fn workspace_hook_runs_before_last_commit() {
+    init_registry();
+    let project = sample_workspace(
+        r#"
+        push = false
+        tag = false
+        consolidate-commits = false
+        pre-release-hook = ["sh", "-c", 'echo "$CRATE_NAME" >> "$WORKSPACE_ROOT/../hooks.log"']
+        workspace-pre-release-hook = ["sh", "-c", 'echo workspace >> ../hooks.log && echo "// generated" >> a/src/lib.rs']
+        "#,
+    );
+
+    snapbox::cmd::Command::cargo_ui()
+        .arg("release")
+        .args(["patch", "--workspace", "--execute", "--no-confirm"])
+        .current_dir(project.root())
+        .assert()
+        .success();
+
+    let hooks =
+        std::fs::read_to_string(cargo_test_support::paths::root().join("hooks.log")).unwrap();
+    snapbox::assert_data_eq!(
+        hooks,
+        str![[r#"
+a
+b
+workspace
+
+"#]]
+    );
+    let repo = git2::Repository::open(project.root()).unwrap();
+    let head = repo.head().unwrap().peel_to_commit().unwrap();
+    assert_eq!(head.summary(), Some("chore: Release b version 0.2.1"));
+    let parent = head.parent(0).unwrap();
+    let path = std::path::Path::new("a/src/lib.rs");
+    assert_ne!(
+        head.tree().unwrap().get_path(path).unwrap().id(),
+        parent.tree().unwrap().get_path(path).unwrap().id()
+    );
+}
@@ -1,7 +1,16 @@
 #![warn(clippy::needless_borrow)]
 #![warn(clippy::redundant_clone)]
 
+mod changelog;
+mod config;
+mod hook;
+mod plan;
+mod post_release;
 mod publish;
+mod registry;
+mod replace;
+mod resume;
+mod status;
 mod version;
 
 fn init_registry() -> cargo_test_support::registry::TestRegistry {
@@ -34,7 +43,7 @@ fn create_default_gitconfig() {
 }
 
 pub fn cargo_exe() -> std::path::PathBuf {
-    snapbox::cmd::cargo_bin("cargo-release")
+    snapbox::cmd::cargo_bin!("cargo-release").to_owned()
 }
 
 /// Test the cargo command
@@ -0,0 +1,192 @@
+use cargo_test_support::cargo_test;
+use cargo_test_support::project;
+use snapbox::str;
+
+use crate::CargoCommand;
+use crate::create_default_gitconfig;
+use crate::init_registry;
+
+fn sample_project() -> cargo_test_support::Project {
+    create_default_gitconfig();
+    let project = project()
+        .file(".gitignore", "/target\n")
+        .file(
+            "Cargo.toml",
+            r#"
+            [package]
+            name = "sample"
+            version = "0.1.0"
+            edition = "2024"
+            "#,
+        )
+        .file("release.toml", "publish = false\npush = false\n")
+        .file("src/lib.rs", "pub fn sample() {}")
+        .build();
+    project.process("cargo").arg("generate-lockfile").run();
+    let repo = cargo_test_support::git::init(&project.root());
+    cargo_test_support::git::add(&repo);
+    cargo_test_support::git::commit(&repo);
+    project
+}
+
+#[cargo_test]
+fn execute_frozen_plan() {
+    init_registry();
+    let project = sample_project();
+    let plan_path = cargo_test_support::paths::root().join("plan.json");
+
+    snapbox::cmd::Command::cargo_ui()
+        .arg("release")
+        .args(["patch", "--no-confirm", "--plan-out"])
+        .arg(&plan_path)
+        .current_dir(project.root())
+        .assert()
+        .success()
+        .stdout_eq(str![])
+        .stderr_eq(str![[r#"
+     Writing release plan to [ROOT]/plan.json
+   Upgrading sample from 0.1.0 to 0.1.1
+warning: aborting release due to dry run; re-run with `--execute`
+
+"#]]);
+
+    snapbox::cmd::Command::cargo_ui()
+        .arg("release")
+        .args(["--execute", "--no-confirm", "--plan"])
+        .arg(&plan_path)
+        .current_dir(project.root())
+        .assert()
+        .success()
+        .stdout_eq(str![[r#"
+[master [..]] chore: Release sample version 0.1.1
+ 2 files changed, 2 insertions(+), 2 deletions(-)
+
+"#]])
+        .stderr_eq(str![[r#"
+   Upgrading sample from 0.1.0 to 0.1.1
+
+"#]]);
+
+    let manifest = std::fs::read_to_string(project.root().join("Cargo.toml")).unwrap();
+    assert!(manifest.contains(r#"version = "0.1.1""#), "{manifest}");
+}
+
+#[cargo_test]
+fn refuse_drifted_plan() {
+    init_registry();
+    let project = sample_project();
+    let plan_path = cargo_test_support::paths::root().join("plan.json");
+
+    snapbox::cmd::Command::cargo_ui()
+        .arg("release")
+        .args(["patch", "--no-confirm", "--plan-out"])
+        .arg(&plan_path)
+        .current_dir(project.root())
+        .assert()
+        .success();
+
+    project.change_file("src/lib.rs", "pub fn sample() {}\npub fn other() {}\n");
+    project
+        .process("git")
+        .args(&["commit", "-am", "Add another function"])
+        .run();
+
+    snapbox::cmd::Command::cargo_ui()
+        .arg("release")
+        .args(["--execute", "--no-confirm", "--plan"])
+        .arg(&plan_path)
+        .current_dir(project.root())
+        .assert()
+        .failure()
+        .stdout_eq(str![])
+        .stderr_eq(str![[r#"
+error: HEAD moved from [..] to [..]
+error: workspace has changed since `[ROOT]/plan.json` was written, regenerate it with `--plan-out`
+
+"#]]);
+}
+
+#[cargo_test]
+fn execute_frozen_plan_for_selected_package() {
+    init_registry();
+    create_default_gitconfig();
+    let project = project()
+        .file(".gitignore", "/target\n")
+        .file(
+            "Cargo.toml",
+            r#"
+            [workspace]
+            members = ["a", "b"]
+            resolver = "3"
+            "#,
+        )
+        .file(
+            "a/Cargo.toml",
+            r#"
+            [package]
+            name = "a"
+            version = "0.1.0"
+            edition = "2024"
+            publish = false
+            "#,
+        )
+        .file("a/src/lib.rs", "pub fn a() {}")
+        .file(
+            "b/Cargo.toml",
+            r#"
+            [package]
+            name = "b"
+            version = "0.2.0"
+            edition = "2024"
+            publish = false
+
+            [dependencies]
+            a = { version = "0.1.0", path = "../a" }
+            "#,
+        )
+        .file("b/src/lib.rs", "pub fn b() {}")
+        .file("release.toml", "push = false\n")
+        .build();
+    project.process("cargo").arg("generate-lockfile").run();
+    let repo = cargo_test_support::git::init(&project.root());
+    cargo_test_support::git::add(&repo);
+    cargo_test_support::git::commit(&repo);
+    let plan_path = cargo_test_support::paths::root().join("plan.json");
+
+    snapbox::cmd::Command::cargo_ui()
+        .arg("release")
+        .args(["patch", "-p", "b", "--no-confirm", "--plan-out"])
+        .arg(&plan_path)
+        .current_dir(project.root())
+        .assert()
+        .success()
+        .stdout_eq(str![])
+        .stderr_eq(str![[r#"
+     Writing release plan to [ROOT]/plan.json
+   Upgrading b from 0.2.0 to 0.2.1
+warning: aborting release due to dry run; re-run with `--execute`
+
+"#]]);
+
+    snapbox::cmd::Command::cargo_ui()
+        .arg("release")
+        .args(["--execute", "--no-confirm", "--plan"])
+        .arg(&plan_path)
+        .current_dir(project.root())
+        .assert()
+        .success()
+        .stdout_eq(str![[r#"
+[master [..]] chore: Release
+ 2 files changed, 2 insertions(+), 2 deletions(-)
+
+"#]])
+        .stderr_eq(str![[r#"
+   Upgrading b from 0.2.0 to 0.2.1
+
+"#]]);
+
+    let a = std::fs::read_to_string(project.root().join("a/Cargo.toml")).unwrap();
+    assert!(a.contains(r#"version = "0.1.0""#), "{a}");
+    let b = std::fs::read_to_string(project.root().join("b/Cargo.toml")).unwrap();
+    assert!(b.contains(r#"version = "0.2.1""#), "{b}");
+}
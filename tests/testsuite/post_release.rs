@@ -0,0 +1,149 @@
+use cargo_test_support::cargo_test;
+use cargo_test_support::project;
+use snapbox::str;
+
+use crate::CargoCommand;
+use crate::create_default_gitconfig;
+use crate::init_registry;
+
+fn sample_workspace(release_toml: &str) -> cargo_test_support::Project {
+    init_registry();
+    create_default_gitconfig();
+    let project = project()
+        .file(".gitignore", "/target\n")
+        .file(
+            "Cargo.toml",
+            r#"
+            [workspace]
+            members = ["a", "b"]
+            resolver = "3"
+            "#,
+        )
+        .file(
+            "a/Cargo.toml",
+            r#"
+            [package]
+            name = "a"
+            version = "0.1.0"
+            edition = "2024"
+            publish = false
+            "#,
+        )
+        .file("a/src/lib.rs", "pub fn a() {}\n")
+        .file(
+            "b/Cargo.toml",
+            r#"
+            [package]
+            name = "b"
+            version = "0.1.0"
+            edition = "2024"
+            publish = false
+
+            [dependencies]
+            a = { version = "0.1.0", path = "../a" }
+            "#,
+        )
+        .file("b/src/lib.rs", "pub fn b() {}\n")
+        .file("release.toml", release_toml)
+        .build();
+    project.process("cargo").arg("generate-lockfile").run();
+    let repo = cargo_test_support::git::init(&project.root());
+    cargo_test_support::git::add(&repo);
+    cargo_test_support::git::commit(&repo);
+    project
+}
+
+#[cargo_test]
+fn post_release_version() {
+    let project = sample_workspace(
+        r#"
+        push = false
+        post-release-version = "{{next_patch}}-dev"
+        "#,
+    );
+
+    snapbox::cmd::Command::cargo_ui()
+        .arg("release")
+        .args(["patch", "--no-confirm"])
+        .current_dir(project.root())
+        .assert()
+        .success()
+        .stdout_eq(str![])
+        .stderr_eq(str![[r#"
+   Upgrading a from 0.1.0 to 0.1.1
+    Updating b's dependency from 0.1.0 to 0.1.1
+   Upgrading b from 0.1.0 to 0.1.1
+   Upgrading a from 0.1.1 to 0.1.2-dev
+    Updating b's dependency from 0.1.0 to 0.1.2-dev
+   Upgrading b from 0.1.1 to 0.1.2-dev
+warning: aborting release due to dry run; re-run with `--execute`
+
+"#]]);
+
+    snapbox::cmd::Command::cargo_ui()
+        .arg("release")
+        .args(["patch", "--execute", "--no-confirm"])
+        .current_dir(project.root())
+        .assert()
+        .success();
+
+    let a = std::fs::read_to_string(project.root().join("a/Cargo.toml")).unwrap();
+    assert!(a.contains(r#"version = "0.1.2-dev""#), "{a}");
+    let b = std::fs::read_to_string(project.root().join("b/Cargo.toml")).unwrap();
+    assert!(b.contains(r#"version = "0.1.2-dev""#), "{b}");
+    assert!(
+        b.contains(r#"a = { version = "0.1.2-dev", path = "../a" }"#),
+        "{b}"
+    );
+
+    snapbox::cmd::Command::new("git")
+        .args(["log", "--format=%s %D"])
+        .current_dir(project.root())
+        .assert()
+        .success()
+        .stdout_eq(str![[r#"
+chore: Start next development iteration HEAD -> master
+chore: Release tag: b-v0.1.1, tag: a-v0.1.1
+test 
+
+"#]]);
+}
+
+#[cargo_test]
+fn post_release_version_per_package() {
+    let project = sample_workspace(
+        r#"
+        push = false
+        consolidate-commits = false
+        post-release-version = "alpha"
+        "#,
+    );
+
+    snapbox::cmd::Command::cargo_ui()
+        .arg("release")
+        .args(["patch", "--execute", "--no-confirm"])
+        .current_dir(project.root())
+        .assert()
+        .success();
+
+    let b = std::fs::read_to_string(project.root().join("b/Cargo.toml")).unwrap();
+    assert!(b.contains(r#"version = "0.1.2-alpha.1""#), "{b}");
+    assert!(
+        b.contains(r#"a = { version = "0.1.2-alpha.1", path = "../a" }"#),
+        "{b}"
+    );
+
+    snapbox::cmd::Command::new("git")
+        .args(["log", "--format=%s %D"])
+        .current_dir(project.root())
+        .assert()
+        .success()
+        .stdout_eq(str![[r#"
+chore: Start b 0.1.2-alpha.1 development HEAD -> master
+chore: Start a 0.1.2-alpha.1 development 
+chore: Release b version 0.1.1 tag: b-v0.1.1, tag: a-v0.1.1
+chore: Release a version 0.1.1 
+test 
+
+"#]]);
+}
@@ -0,0 +1,782 @@
+use cargo_test_support::cargo_test;
+use cargo_test_support::project;
+use cargo_test_support::registry::Package;
+use cargo_test_support::registry::RegistryBuilder;
+use snapbox::str;
+
+use crate::CargoCommand;
+use crate::create_default_gitconfig;
+use crate::init_registry;
+
+fn sample_project(registries: &[&str]) -> cargo_test_support::Project {
+    create_default_gitconfig();
+    let (publish, release_toml) = match registries {
+        [] => (String::new(), String::new()),
+        [registry] => (
+            format!("publish = [\"{registry}\"]"),
+            format!("registry = \"{registry}\"\n"),
+        ),
+        registries => {
+            let registries = registries
+                .iter()
+                .map(|r| format!("\"{r}\""))
+                .collect::<Vec<_>>()
+                .join(", ");
+            (
+                format!("publish = [{registries}]"),
+                format!("registry = [{registries}]\n"),
+            )
+        }
+    };
+    let project = project()
+        .file(".gitignore", "/target\n")
+        .file(
+            "Cargo.toml",
+            &format!(
+                r#"
+            [package]
+            name = "sample"
+            version = "0.1.0"
+            edition = "2024"
+            description = "A sample package"
+            license = "MIT"
+            repository = "https://example.com/sample"
+            {publish}
+            "#
+            ),
+        )
+        .file("release.toml", &release_toml)
+        .file("src/lib.rs", "pub fn sample() {}\n")
+        .build();
+    project.process("cargo").arg("generate-lockfile").run();
+    let repo = cargo_test_support::git::init(&project.root());
+    cargo_test_support::git::add(&repo);
+    cargo_test_support::git::commit(&repo);
+    cargo_test_support::git::tag(&repo, "v0.1.0");
+    project
+}
+
+#[cargo_test]
+fn status_sparse_registry() {
+    init_registry();
+    let _registry = RegistryBuilder::new().alternative().http_index().build();
+    Package::new("sample", "0.1.0").alternative(true).publish();
+    let project = sample_project(&["alternative"]);
+
+    snapbox::cmd::Command::cargo_ui()
+        .arg("release")
+        .arg("status")
+        .current_dir(project.root())
+        .assert()
+        .success()
+        .stdout_eq(str![[r#"
+PACKAGE  VERSION  TAG     COMMITS  PUBLISHED  LATEST  BUMP
+sample   0.1.0    v0.1.0  0        yes        0.1.0   -
+
+"#]])
+        .stderr_eq(str![]);
+}
+
+#[cargo_test]
+fn status_sparse_registry_auth_required() {
+    init_registry();
+    let _registry = RegistryBuilder::new()
+        .alternative()
+        .http_index()
+        .auth_required()
+        .build();
+    Package::new("sample", "0.1.0").alternative(true).publish();
+    let project = sample_project(&["alternative"]);
+
+    snapbox::cmd::Command::cargo_ui()
+        .arg("release")
+        .arg("status")
+        .current_dir(project.root())
+        .assert()
+        .success()
+        .stdout_eq(str![[r#"
+PACKAGE  VERSION  TAG     COMMITS  PUBLISHED  LATEST  BUMP
+sample   0.1.0    v0.1.0  0        yes        0.1.0   -
+
+"#]])
+        .stderr_eq(str![]);
+}
+
+#[cargo_test]
+fn skip_published_in_sparse_registry() {
+    init_registry();
+    let _registry = RegistryBuilder::new().alternative().http_index().build();
+    Package::new("sample", "0.1.0").alternative(true).publish();
+    let project = sample_project(&["alternative"]);
+
+    snapbox::cmd::Command::cargo_ui()
+        .arg("release")
+        .args(["publish", "--no-confirm"])
+        .current_dir(project.root())
+        .assert()
+        .code(2)
+        .stdout_eq(str![])
+        .stderr_eq(str![[r#"
+warning: disabled due to previous publish (0.1.0), skipping sample
+error: no packages selected
+
+"#]]);
+}
+
+#[cargo_test]
+fn skip_published_in_git_index() {
+    // Replaces crates.io with a `file://` git index
+    init_registry();
+    Package::new("sample", "0.1.0").publish();
+    let project = sample_project(&[]);
+
+    snapbox::cmd::Command::cargo_ui()
+        .arg("release")
+        .args(["publish", "--no-confirm"])
+        .current_dir(project.root())
+        .assert()
+        .code(2)
+        .stdout_eq(str![])
+        .stderr_eq(str![[r#"
+warning: disabled due to previous publish (0.1.0), skipping sample
+error: no packages selected
+
+"#]]);
+}
+
+#[cargo_test]
+fn skip_published_in_local_registry() {
+    let config_path = cargo_test_support::paths::cargo_home().join("config.toml");
+    std::fs::create_dir_all(config_path.parent().unwrap()).unwrap();
+    std::fs::write(
+        &config_path,
+        format!(
+            r#"
+            [source.crates-io]
+            replace-with = "local"
+
+            [source.local]
+            local-registry = '{}'
+            "#,
+            cargo_test_support::registry::registry_path().display()
+        ),
+    )
+    .unwrap();
+    Package::new("sample", "0.1.0").local(true).publish();
+    let project = sample_project(&[]);
+
+    snapbox::cmd::Command::cargo_ui()
+        .arg("release")
+        .args(["publish", "--no-confirm"])
+        .current_dir(project.root())
+        .assert()
+        .code(2)
+        .stdout_eq(str![])
+        .stderr_eq(str![[r#"
+warning: disabled due to previous publish (0.1.0), skipping sample
+error: no packages selected
+
+"#]]);
+}
+
+#[cargo_test]
+fn verify_dependencies_in_sparse_registry() {
+    init_registry();
+    let _registry = RegistryBuilder::new().alternative().http_index().build();
+    create_default_gitconfig();
+    let project = project()
+        .file(".gitignore", "/target\n")
+        .file(
+            "Cargo.toml",
+            r#"
+            [workspace]
+            members = ["a", "b"]
+            resolver = "3"
+            "#,
+        )
+        .file(
+            "a/Cargo.toml",
+            r#"
+            [package]
+            name = "a"
+            version = "0.1.0"
+            edition = "2024"
+            description = "A sample package"
+            license = "MIT"
+            repository = "https://example.com/sample"
+            publish = ["alternative"]
+
+            [dependencies]
+            b = { version = "0.1.0", path = "../b", registry = "alternative" }
+            "#,
+        )
+        .file("a/src/lib.rs", "pub fn a() {}\n")
+        .file(
+            "b/Cargo.toml",
+            r#"
+            [package]
+            name = "b"
+            version = "0.1.0"
+            edition = "2024"
+            description = "A sample package"
+            license = "MIT"
+            repository = "https://example.com/sample"
+            publish = ["alternative"]
+            "#,
+        )
+        .file("b/src/lib.rs", "pub fn b() {}\n")
+        .file("release.toml", "registry = \"alternative\"\n")
+        .build();
+    project.process("cargo").arg("generate-lockfile").run();
+    let repo = cargo_test_support::git::init(&project.root());
+    cargo_test_support::git::add(&repo);
+    cargo_test_support::git::commit(&repo);
+
+    snapbox::cmd::Command::cargo_ui()
+        .arg("release")
+        .args(["publish", "-p", "a", "--no-confirm"])
+        .current_dir(project.root())
+        .assert()
+        .failure()
+        .stdout_eq(str![])
+        .stderr_eq(str![[r#"
+error: a 0.1.0 depends on unpublished workspace package b 0.1.0
+warning: push target `origin/master` doesn't exist
+  Publishing a
+    Updating `alternative` index
+   Packaging a v0.1.0 ([ROOT]/foo/a)
+    Updating `alternative` index
+error: failed to prepare local package for uploading
+
+Caused by:
+  no matching package named `b` found
+  location searched: `alternative` index
+  required by package `a v0.1.0 ([ROOT]/foo/a)`
+
+"#]]);
+}
+
+#[cargo_test]
+fn status_sparse_registry_token_in_config() {
+    init_registry();
+    let registry = RegistryBuilder::new()
+        .alternative()
+        .http_index()
+        .auth_required()
+        .no_configure_token()
+        .build();
+    Package::new("sample", "0.1.0").alternative(true).publish();
+    let project = sample_project(&["alternative"]);
+    project.change_file(
+        ".cargo/config.toml",
+        &format!(
+            "[registries.alternative]\ntoken = \"{}\"\n",
+            registry.token()
+        ),
+    );
+
+    snapbox::cmd::Command::cargo_ui()
+        .arg("release")
+        .arg("status")
+        .current_dir(project.root())
+        .assert()
+        .success()
+        .stdout_eq(str![[r#"
+PACKAGE  VERSION  TAG     COMMITS  PUBLISHED  LATEST  BUMP
+sample   0.1.0    v0.1.0  0        yes        0.1.0   -
+
+"#]])
+        .stderr_eq(str![]);
+}
+
+#[cargo_test]
+fn status_sparse_registry_on_filesystem() {
+    init_registry();
+    let _registry = RegistryBuilder::new().alternative().http_index().build();
+    Package::new("sample", "0.1.0").alternative(true).publish();
+    let project = sample_project(&["alternative"]);
+    let index = cargo_test_support::registry::alt_registry_path();
+    project.change_file(
+        ".cargo/config.toml",
+        &format!(
+            "[registries.alternative]\nindex = \"sparse+file://{}/\"\n",
+            index.display()
+        ),
+    );
+
+    snapbox::cmd::Command::cargo_ui()
+        .arg("release")
+        .arg("status")
+        .current_dir(project.root())
+        .assert()
+        .success()
+        .stdout_eq(str![[r#"
+PACKAGE  VERSION  TAG     COMMITS  PUBLISHED  LATEST  BUMP
+sample   0.1.0    v0.1.0  0        yes        0.1.0   -
+
+"#]])
+        .stderr_eq(str![]);
+}
+
+#[cargo_test]
+fn status_unsupported_index() {
+    init_registry();
+    let project = sample_project(&["alternative"]);
+    project.change_file(
+        ".cargo/config.toml",
+        "[registries.alternative]\nindex = \"https://example.com/index.git\"\n",
+    );
+
+    snapbox::cmd::Command::cargo_ui()
+        .arg("release")
+        .arg("status")
+        .current_dir(project.root())
+        .assert()
+        .failure()
+        .stdout_eq(str![])
+        .stderr_eq(str![[r#"
+error: cannot read the index at `https://example.com/index.git`, only sparse indexes, local registries and `file://` git indexes are supported
+
+"#]]);
+}
+
+#[cargo_test]
+fn verify_dependencies_from_another_registry() {
+    init_registry();
+    let _registry = RegistryBuilder::new().alternative().http_index().build();
+    let _mirror = RegistryBuilder::new()
+        .alternative_named("mirror")
+        .http_index()
+        .build();
+    Package::new("dep", "0.1.0").alternative(true).publish();
+    create_default_gitconfig();
+    let project = project()
+        .file(".gitignore", "/target\n")
+        .file(
+            "Cargo.toml",
+            r#"
+            [package]
+            name = "sample"
+            version = "0.1.0"
+            edition = "2024"
+            description = "A sample package"
+            license = "MIT"
+            repository = "https://example.com/sample"
+            publish = ["mirror"]
+
+            [dependencies]
+            dep = { version = "0.1.0", registry = "alternative" }
+            "#,
+        )
+        .file("release.toml", "registry = \"mirror\"\n")
+        .file("src/lib.rs", "pub fn sample() {}\n")
+        .build();
+    project.process("cargo").arg("generate-lockfile").run();
+    let repo = cargo_test_support::git::init(&project.root());
+    cargo_test_support::git::add(&repo);
+    cargo_test_support::git::commit(&repo);
+
+    snapbox::cmd::Command::cargo_ui()
+        .arg("release")
+        .args(["publish", "--no-confirm", "--no-verify"])
+        .current_dir(project.root())
+        .assert()
+        .success()
+        .stdout_eq(str![])
+        .stderr_eq(str![[r#"
+warning: push target `origin/master` doesn't exist
+  Publishing sample
+    Updating `mirror` index
+   Packaging sample v0.1.0 ([ROOT]/foo)
+    Updating `alternative` index
+    Packaged 7 files, [FILE_SIZE]B ([FILE_SIZE]B compressed)
+   Uploading sample v0.1.0 ([ROOT]/foo)
+warning: aborting upload due to dry run
+warning: aborting release due to dry run; re-run with `--execute`
+
+"#]]);
+}
+
+#[cargo_test]
+fn publish_waits_for_sparse_registry() {
+    init_registry();
+    let _registry = RegistryBuilder::new()
+        .alternative()
+        .http_index()
+        .http_api()
+        .build();
+    let project = sample_project(&["alternative"]);
+
+    snapbox::cmd::Command::cargo_ui()
+        .arg("release")
+        .args(["publish", "--execute", "--no-confirm", "--no-verify"])
+        .current_dir(project.root())
+        .env("CARGO_RELEASE_PUBLISH_WAIT", "index")
+        .assert()
+        .success()
+        .stdout_eq(str![])
+        .stderr_eq(str![[r#"
+warning: push target `origin/master` doesn't exist
+  Publishing sample
+    Updating `alternative` index
+   Packaging sample v0.1.0 ([ROOT]/foo)
+    Packaged 7 files, [FILE_SIZE]B ([FILE_SIZE]B compressed)
+   Uploading sample v0.1.0 ([ROOT]/foo)
+    Uploaded sample v0.1.0 to registry `alternative`
+note: waiting for sample v0.1.0 to be available at registry `alternative`
+help: you may press ctrl-c to skip waiting; the crate should be available shortly
+   Published sample v0.1.0 at registry `alternative`
+     Waiting for sample 0.1.0 to appear in alternative
+
+"#]]);
+}
+
+#[cargo_test]
+fn parallel_publish_in_dependency_order() {
+    init_registry();
+    let _registry = RegistryBuilder::new()
+        .alternative()
+        .http_index()
+        .http_api()
+        .build();
+    create_default_gitconfig();
+    let package = |name: &str, dependencies: &str| {
+        format!(
+            r#"
+            [package]
+            name = "{name}"
+            version = "0.1.0"
+            edition = "2024"
+            description = "A sample package"
+            license = "MIT"
+            repository = "https://example.com/sample"
+            publish = ["alternative"]
+
+            [dependencies]
+            {dependencies}
+            "#
+        )
+    };
+    let project = project()
+        .file(".gitignore", "/target\n")
+        .file(
+            "Cargo.toml",
+            r#"
+            [workspace]
+            resolver = "2"
+            members = ["base", "app", "other"]
+            "#,
+        )
+        .file(
+            "release.toml",
+            r#"
+            registry = "alternative"
+            publish-jobs = 2
+            pre-publish-hook = ["sh", "-c", "echo {{crate_name}} >> ../hooks.log"]
+            "#,
+        )
+        .file("base/Cargo.toml", &package("base", ""))
+        .file("base/src/lib.rs", "pub fn base() {}\n")
+        .file(
+            "app/Cargo.toml",
+            &package(
+                "app",
+                r#"base = { path = "../base", version = "0.1.0", registry = "alternative" }"#,
+            ),
+        )
+        .file("app/src/lib.rs", "pub fn app() {}\n")
+        .file("other/Cargo.toml", &package("other", ""))
+        .file("other/src/lib.rs", "pub fn other() {}\n")
+        .build();
+    project.process("cargo").arg("generate-lockfile").run();
+    let repo = cargo_test_support::git::init(&project.root());
+    cargo_test_support::git::add(&repo);
+    cargo_test_support::git::commit(&repo);
+
+    snapbox::cmd::Command::cargo_ui()
+        .arg("release")
+        .args(["publish", "--execute", "--no-confirm", "--no-verify"])
+        .current_dir(project.root())
+        .assert()
+        // Output from the concurrent `cargo publish` calls is interleaved
+        .success();
+
+    let hooks = std::fs::read_to_string(project.root().join("hooks.log")).unwrap();
+    let hooks = hooks.lines().collect::<Vec<_>>();
+    let position = |name| hooks.iter().position(|h| *h == name).unwrap();
+    assert_eq!(hooks.len(), 3, "{hooks:?}");
+    assert!(position("base") < position("app"), "{hooks:?}");
+    assert!(hooks.contains(&"other"), "{hooks:?}");
+
+    snapbox::cmd::Command::cargo_ui()
+        .arg("release")
+        .arg("status")
+        .current_dir(project.root())
+        .assert()
+        .success()
+        .stdout_eq(str![[r#"
+PACKAGE  VERSION  TAG  COMMITS  PUBLISHED  LATEST  BUMP
+base     0.1.0    -    -        yes        0.1.0   -
+app      0.1.0    -    -        yes        0.1.0   -
+other    0.1.0    -    -        yes        0.1.0   -
+
+"#]])
+        .stderr_eq(str![]);
+}
+
+/// Respond to the first publish with a server error, optionally after accepting it
+fn fail_first_publish(accept: bool) -> cargo_test_support::registry::TestRegistry {
+    let failed = std::sync::atomic::AtomicBool::new(false);
+    RegistryBuilder::new()
+        .alternative()
+        .http_index()
+        .http_api()
+        .add_responder("/api/v1/crates/new", move |req, server| {
+            if failed.swap(true, std::sync::atomic::Ordering::SeqCst) {
+                return server.check_authorized_publish(req);
+            }
+            if accept {
+                server.check_authorized_publish(req);
+            }
+            server.internal_server_error(req)
+        })
+        .build()
+}
+
+#[cargo_test]
+fn publish_retries_transient_failure() {
+    init_registry();
+    let _registry = fail_first_publish(false);
+    let project = sample_project(&["alternative"]);
+
+    snapbox::cmd::Command::cargo_ui()
+        .arg("release")
+        .args(["publish", "--execute", "--no-confirm", "--no-verify"])
+        .current_dir(project.root())
+        .env("CARGO_RELEASE_PUBLISH_RETRIES", "1")
+        .assert()
+        .success()
+        .stdout_eq(str![])
+        .stderr_eq(str![[r#"
+warning: push target `origin/master` doesn't exist
+  Publishing sample
+    Updating `alternative` index
+   Packaging sample v0.1.0 ([ROOT]/foo)
+    Packaged 7 files, [FILE_SIZE]B ([FILE_SIZE]B compressed)
+   Uploading sample v0.1.0 ([ROOT]/foo)
+error: failed to publish sample v0.1.0 to registry at http://127.0.0.1:[..]/
+
+Caused by:
+  failed to get a 200 OK response, got 500
+  headers:
+  	HTTP/1.1 500
+  	Content-Length: 21
+  	Connection: close
+  	
+  body:
+  internal server error
+     Waiting 2s to retry publishing sample (1/1)
+    Updating `alternative` index
+   Packaging sample v0.1.0 ([ROOT]/foo)
+    Packaged 7 files, [FILE_SIZE]B ([FILE_SIZE]B compressed)
+   Uploading sample v0.1.0 ([ROOT]/foo)
+    Uploaded sample v0.1.0 to registry `alternative`
+note: waiting for sample v0.1.0 to be available at registry `alternative`
+help: you may press ctrl-c to skip waiting; the crate should be available shortly
+   Published sample v0.1.0 at registry `alternative`
+
+"#]]);
+}
+
+#[cargo_test]
+fn publish_retry_checks_index() {
+    init_registry();
+    let _registry = fail_first_publish(true);
+    let project = sample_project(&["alternative"]);
+
+    snapbox::cmd::Command::cargo_ui()
+        .arg("release")
+        .args(["publish", "--execute", "--no-confirm", "--no-verify"])
+        .current_dir(project.root())
+        .env("CARGO_RELEASE_PUBLISH_RETRIES", "1")
+        .assert()
+        .success()
+        .stdout_eq(str![])
+        .stderr_eq(str![[r#"
+warning: push target `origin/master` doesn't exist
+  Publishing sample
+    Updating `alternative` index
+   Packaging sample v0.1.0 ([ROOT]/foo)
+    Packaged 7 files, [FILE_SIZE]B ([FILE_SIZE]B compressed)
+   Uploading sample v0.1.0 ([ROOT]/foo)
+error: failed to publish sample v0.1.0 to registry at http://127.0.0.1:[..]/
+
+Caused by:
+  failed to get a 200 OK response, got 500
+  headers:
+  	HTTP/1.1 500
+  	Content-Length: 21
+  	Connection: close
+  	
+  body:
+  internal server error
+     Waiting 2s to retry publishing sample (1/1)
+note: sample 0.1.0 was published despite the failure
+
+"#]]);
+}
+
+#[cargo_test]
+fn publish_to_several_registries() {
+    init_registry();
+    let _registry = RegistryBuilder::new()
+        .alternative()
+        .http_index()
+        .http_api()
+        .build();
+    let _mirror = RegistryBuilder::new()
+        .alternative_named("mirror")
+        .http_index()
+        .http_api()
+        .build();
+    let project = sample_project(&["alternative", "mirror"]);
+
+    snapbox::cmd::Command::cargo_ui()
+        .arg("release")
+        .args(["publish", "--execute", "--no-confirm", "--no-verify"])
+        .current_dir(project.root())
+        .assert()
+        .success()
+        .stdout_eq(str![])
+        .stderr_eq(str![[r#"
+warning: push target `origin/master` doesn't exist
+  Publishing sample
+    Updating `alternative` index
+   Packaging sample v0.1.0 ([ROOT]/foo)
+    Packaged 7 files, [FILE_SIZE]B ([FILE_SIZE]B compressed)
+   Uploading sample v0.1.0 ([ROOT]/foo)
+    Uploaded sample v0.1.0 to registry `alternative`
+note: waiting for sample v0.1.0 to be available at registry `alternative`
+help: you may press ctrl-c to skip waiting; the crate should be available shortly
+   Published sample v0.1.0 at registry `alternative`
+  Publishing sample
+    Updating `mirror` index
+   Packaging sample v0.1.0 ([ROOT]/foo)
+    Packaged 7 files, [FILE_SIZE]B ([FILE_SIZE]B compressed)
+   Uploading sample v0.1.0 ([ROOT]/foo)
+    Uploaded sample v0.1.0 to registry `mirror`
+note: waiting for sample v0.1.0 to be available at registry `mirror`
+help: you may press ctrl-c to skip waiting; the crate should be available shortly
+   Published sample v0.1.0 at registry `mirror`
+
+"#]]);
+}
+
+#[cargo_test]
+fn publish_skips_registry_with_version() {
+    init_registry();
+    let _registry = RegistryBuilder::new()
+        .alternative()
+        .http_index()
+        .http_api()
+        .build();
+    let _mirror = RegistryBuilder::new()
+        .alternative_named("mirror")
+        .http_index()
+        .http_api()
+        .build();
+    Package::new("sample", "0.1.0").alternative(true).publish();
+    let project = sample_project(&["alternative", "mirror"]);
+
+    snapbox::cmd::Command::cargo_ui()
+        .arg("release")
+        .args(["publish", "--execute", "--no-confirm", "--no-verify"])
+        .current_dir(project.root())
+        .assert()
+        .success()
+        .stdout_eq(str![])
+        .stderr_eq(str![[r#"
+warning: skipping sample for alternative due to previous publish (0.1.0)
+warning: push target `origin/master` doesn't exist
+  Publishing sample
+    Updating `mirror` index
+   Packaging sample v0.1.0 ([ROOT]/foo)
+    Packaged 7 files, [FILE_SIZE]B ([FILE_SIZE]B compressed)
+   Uploading sample v0.1.0 ([ROOT]/foo)
+    Uploaded sample v0.1.0 to registry `mirror`
+note: waiting for sample v0.1.0 to be available at registry `mirror`
+help: you may press ctrl-c to skip waiting; the crate should be available shortly
+   Published sample v0.1.0 at registry `mirror`
+
+"#]]);
+}
+
+#[cargo_test]
+fn publish_failure_reports_registry() {
+    init_registry();
+    let _registry = RegistryBuilder::new()
+        .alternative()
+        .http_index()
+        .http_api()
+        .add_responder("/api/v1/crates/new", |req, server| {
+            server.internal_server_error(req)
+        })
+        .build();
+    let _mirror = RegistryBuilder::new()
+        .alternative_named("mirror")
+        .http_index()
+        .http_api()
+        .build();
+    let project = sample_project(&["alternative", "mirror"]);
+
+    snapbox::cmd::Command::cargo_ui()
+        .arg("release")
+        .args(["publish", "--execute", "--no-confirm", "--no-verify"])
+        .current_dir(project.root())
+        .assert()
+        .failure()
+        .stdout_eq(str![])
+        .stderr_eq(str![[r#"
+warning: push target `origin/master` doesn't exist
+  Publishing sample
+    Updating `alternative` index
+   Packaging sample v0.1.0 ([ROOT]/foo)
+    Packaged 7 files, [FILE_SIZE]B ([FILE_SIZE]B compressed)
+   Uploading sample v0.1.0 ([ROOT]/foo)
+error: failed to publish sample v0.1.0 to registry at http://127.0.0.1:[..]/
+
+Caused by:
+  failed to get a 200 OK response, got 500
+  headers:
+  	HTTP/1.1 500
+  	Content-Length: 21
+  	Connection: close
+  	
+  body:
+  internal server error
+error: failed to publish to alternative
+  Publishing sample
+    Updating `mirror` index
+   Packaging sample v0.1.0 ([ROOT]/foo)
+    Packaged 7 files, [FILE_SIZE]B ([FILE_SIZE]B compressed)
+   Uploading sample v0.1.0 ([ROOT]/foo)
+    Uploaded sample v0.1.0 to registry `mirror`
+note: waiting for sample v0.1.0 to be available at registry `mirror`
+help: you may press ctrl-c to skip waiting; the crate should be available shortly
+   Published sample v0.1.0 at registry `mirror`
+
+"#]]);
+
+    snapbox::cmd::Command::cargo_ui()
+        .arg("release")
+        .arg("status")
+        .current_dir(project.root())
+        .assert()
+        .success()
+        .stdout_eq(str![[r#"
+PACKAGE  VERSION  TAG     COMMITS  PUBLISHED  LATEST  BUMP
+sample   0.1.0    v0.1.0  0        -          0.1.0   -
+
+"#]])
+        .stderr_eq(str![]);
+}
@@ -0,0 +1,233 @@
+use cargo_test_support::cargo_test;
+use cargo_test_support::project;
+use snapbox::str;
+
+use crate::CargoCommand;
+use crate::create_default_gitconfig;
+use crate::init_registry;
+
+fn sample_project(release_toml: &str) -> cargo_test_support::Project {
+    sample_project_with_docs(release_toml, &[])
+}
+
+fn sample_project_with_docs(
+    release_toml: &str,
+    docs: &[(&str, &str)],
+) -> cargo_test_support::Project {
+    create_default_gitconfig();
+    let mut project = project()
+        .file(".gitignore", "/target\n")
+        .file(
+            "Cargo.toml",
+            r#"
+            [package]
+            name = "sample"
+            version = "0.1.0"
+            edition = "2024"
+            "#,
+        )
+        .file("release.toml", release_toml)
+        .file(
+            "package.json",
+            r#"{
+  "name": "sample",
+  "version": "0.1.0"
+}
+"#,
+        )
+        .file("src/lib.rs", "pub fn sample() {}");
+    for (path, body) in docs {
+        project = project.file(path, body);
+    }
+    let project = project.build();
+    project.process("cargo").arg("generate-lockfile").run();
+    let repo = cargo_test_support::git::init(&project.root());
+    cargo_test_support::git::add(&repo);
+    cargo_test_support::git::commit(&repo);
+    project
+}
+
+#[cargo_test]
+fn replace_key() {
+    init_registry();
+    let project = sample_project(
+        r#"
+        pre-release-replacements = [
+          {file="package.json", key="version", replace="{{version}}"},
+        ]
+        "#,
+    );
+
+    snapbox::cmd::Command::cargo_ui()
+        .arg("release")
+        .args(["version", "patch", "--execute", "--no-confirm"])
+        .current_dir(project.root())
+        .assert()
+        .success();
+
+    snapbox::cmd::Command::cargo_ui()
+        .arg("release")
+        .args(["replace", "--execute", "--no-confirm"])
+        .current_dir(project.root())
+        .assert()
+        .success()
+        .stdout_eq(str![])
+        .stderr_eq(str![[r#"
+warning: uncommitted changes detected, please resolve before release:
+           Cargo.lock (Status(WT_MODIFIED))
+           Cargo.toml (Status(WT_MODIFIED))
+warning: push target `origin/master` doesn't exist
+
+"#]]);
+
+    let package = std::fs::read_to_string(project.root().join("package.json")).unwrap();
+    snapbox::assert_data_eq!(
+        package,
+        str![[r#"
+{
+  "name": "sample",
+  "version": "0.1.1"
+}
+
+"#]]
+    );
+}
+
+#[cargo_test]
+fn replace_missing_key() {
+    init_registry();
+    let project = sample_project(
+        r#"
+        pre-release-replacements = [
+          {file="package.json", key="engines.node", replace="{{version}}"},
+        ]
+        "#,
+    );
+
+    snapbox::cmd::Command::cargo_ui()
+        .arg("release")
+        .args(["replace", "--no-confirm"])
+        .current_dir(project.root())
+        .assert()
+        .failure()
+        .stdout_eq(str![])
+        .stderr_eq(str![[r#"
+warning: push target `origin/master` doesn't exist
+error: for `engines.node` in 'package.json', no value found at key
+
+"#]]);
+}
+
+const DOCS: &[(&str, &str)] = &[
+    (
+        "docs/install.md",
+        "sample = \"0.0.9\"\n\nsample = \"0.0.9\"\n",
+    ),
+    ("docs/guide/intro.md", "sample = \"0.0.9\"\n"),
+    ("docs/changes.md", "No versions here\n"),
+];
+
+#[cargo_test]
+fn replace_glob_per_file() {
+    init_registry();
+    let project = sample_project_with_docs(
+        r#"
+        pre-release-replacements = [
+          {file="docs/**/*.md", search="sample = \"[0-9.]+\"", replace="sample = \"{{version}}\""},
+        ]
+        "#,
+        DOCS,
+    );
+
+    snapbox::cmd::Command::cargo_ui()
+        .arg("release")
+        .args(["replace", "--no-confirm"])
+        .current_dir(project.root())
+        .assert()
+        .failure()
+        .stdout_eq(str![])
+        .stderr_eq(str![[r#"
+warning: push target `origin/master` doesn't exist
+error: for `sample = "[0-9.]+"` in 'docs/changes.md', at least 1 replacements expected, found 0
+
+"#]]);
+}
+
+#[cargo_test]
+fn replace_glob_total() {
+    init_registry();
+    let project = sample_project_with_docs(
+        r#"
+        pre-release-replacements = [
+          {file="docs/**/*.md", search="sample = \"[0-9.]+\"", replace="sample = \"{{version}}\"", exactly=3, counts="total"},
+        ]
+        "#,
+        DOCS,
+    );
+
+    snapbox::cmd::Command::cargo_ui()
+        .arg("release")
+        .args(["replace", "--no-confirm"])
+        .current_dir(project.root())
+        .assert()
+        .success()
+        .stdout_eq(str![])
+        .stderr_eq(str![[r#"
+warning: push target `origin/master` doesn't exist
+   Replacing in docs/guide/intro.md
+--- docs/guide/intro.md	original
++++ docs/guide/intro.md	replaced
+@@ -1 +1 @@
+-sample = "0.0.9"
++sample = "0.1.0"
+
+   Replacing in docs/install.md
+--- docs/install.md	original
++++ docs/install.md	replaced
+@@ -1,3 +1,3 @@
+-sample = "0.0.9"
++sample = "0.1.0"
+ 
+-sample = "0.0.9"
++sample = "0.1.0"
+
+warning: aborting release due to dry run; re-run with `--execute`
+
+"#]]);
+}
+
+#[cargo_test]
+fn replace_glob_in_dot_directory() {
+    init_registry();
+    let project = sample_project_with_docs(
+        r#"
+        pre-release-replacements = [
+          {file="**/*.yml", search="sample@[0-9.]+", replace="sample@{{version}}"},
+        ]
+        "#,
+        &[
+            (".github/workflows/ci.yml", "uses: sample@0.0.9\n"),
+            ("target/ci.yml", "uses: sample@0.0.9\n"),
+        ],
+    );
+
+    snapbox::cmd::Command::cargo_ui()
+        .arg("release")
+        .args(["replace", "--no-confirm"])
+        .current_dir(project.root())
+        .assert()
+        .success()
+        .stdout_eq(str![])
+        .stderr_eq(str![[r#"
+warning: push target `origin/master` doesn't exist
+   Replacing in .github/workflows/ci.yml
+--- .github/workflows/ci.yml	original
++++ .github/workflows/ci.yml	replaced
+@@ -1 +1 @@
+-uses: sample@0.0.9
++uses: sample@0.1.0
+
+warning: aborting release due to dry run; re-run with `--execute`
+
+"#]]);
+}
@@ -0,0 +1,464 @@
+use cargo_test_support::cargo_test;
+use cargo_test_support::project;
+use cargo_test_support::registry::RegistryBuilder;
+use snapbox::str;
+
+use crate::CargoCommand;
+use crate::create_default_gitconfig;
+use crate::init_registry;
+
+fn sample_project(release_toml: &str) -> cargo_test_support::Project {
+    create_default_gitconfig();
+    let project = project()
+        .file(".gitignore", "/target\n")
+        .file(
+            "Cargo.toml",
+            r#"
+            [package]
+            name = "sample"
+            version = "0.1.0"
+            edition = "2024"
+            "#,
+        )
+        .file("release.toml", release_toml)
+        .file("src/lib.rs", "pub fn sample() {}")
+        .build();
+    project.process("cargo").arg("generate-lockfile").run();
+    let repo = cargo_test_support::git::init(&project.root());
+    cargo_test_support::git::add(&repo);
+    cargo_test_support::git::commit(&repo);
+    project
+        .process("git")
+        .args(&["remote", "add", "origin", "../remote.git"])
+        .run();
+    project
+}
+
+fn create_remote() {
+    git2::Repository::init_bare(cargo_test_support::paths::root().join("remote.git")).unwrap();
+}
+
+#[cargo_test]
+fn resume_after_failed_push() {
+    init_registry();
+    let project = sample_project("publish = false\n");
+
+    snapbox::cmd::Command::cargo_ui()
+        .arg("release")
+        .args(["patch", "--execute", "--no-confirm"])
+        .current_dir(project.root())
+        .assert()
+        .failure()
+        .stdout_eq(str![[r#"
+[master [..]] chore: Release sample version 0.1.1
+ 2 files changed, 2 insertions(+), 2 deletions(-)
+
+"#]])
+        .stderr_eq(str![[r#"
+warning: push target `origin/master` doesn't exist
+   Upgrading sample from 0.1.0 to 0.1.1
+warning: push target `origin/master` doesn't exist
+     Pushing Pushing master, v0.1.1 to origin
+fatal: '../remote.git' does not appear to be a git repository
+fatal: Could not read from remote repository.
+
+Please make sure you have the correct access rights
+and the repository exists.
+
+"#]]);
+
+    snapbox::cmd::Command::cargo_ui()
+        .arg("release")
+        .args(["patch", "--execute", "--no-confirm"])
+        .current_dir(project.root())
+        .assert()
+        .failure()
+        .stdout_eq(str![])
+        .stderr_eq(str![[r#"
+error: a release was interrupted, pass `--resume` to continue it or remove `[ROOT]/foo/.git/cargo-release/journal.json`
+
+"#]]);
+
+    create_remote();
+
+    snapbox::cmd::Command::cargo_ui()
+        .arg("release")
+        .args(["--resume", "--execute", "--no-confirm"])
+        .current_dir(project.root())
+        .assert()
+        .success()
+        .stdout_eq(str![])
+        .stderr_eq(str![[r#"
+warning: push target `origin/master` doesn't exist
+    Skipping version bump of sample, already done
+    Skipping changelog for sample, already done
+    Skipping replacements for sample, already done
+    Skipping pre-release hook for sample, already done
+    Skipping release commit for sample, already done
+    Skipping tag for sample, already done
+warning: push target `origin/master` doesn't exist
+     Pushing Pushing master, v0.1.1 to origin
+To ../remote.git
+ * [new branch]      master -> master
+ * [new tag]         v0.1.1 -> v0.1.1
+
+"#]]);
+
+    let remote =
+        git2::Repository::open(cargo_test_support::paths::root().join("remote.git")).unwrap();
+    assert!(remote.find_reference("refs/tags/v0.1.1").is_ok());
+    assert!(
+        !project
+            .root()
+            .join(".git/cargo-release/journal.json")
+            .exists()
+    );
+}
+
+#[cargo_test]
+fn rollback_failed_hook() {
+    init_registry();
+    let project = sample_project(
+        r#"
+        publish = false
+        pre-release-hook = ["sh", "-c", "exit 1"]
+        "#,
+    );
+
+    snapbox::cmd::Command::cargo_ui()
+        .arg("release")
+        .args(["patch", "--execute", "--no-confirm"])
+        .current_dir(project.root())
+        .assert()
+        .failure()
+        .stdout_eq(str![])
+        .stderr_eq(str![[r#"
+warning: push target `origin/master` doesn't exist
+   Upgrading sample from 0.1.0 to 0.1.1
+error: release of sample aborted by non-zero return of pre-release hook.
+Rolling back release
+   Resetting to [..]
+
+"#]]);
+
+    let manifest = std::fs::read_to_string(project.root().join("Cargo.toml")).unwrap();
+    assert!(manifest.contains(r#"version = "0.1.0""#), "{manifest}");
+    assert!(
+        !project
+            .root()
+            .join(".git/cargo-release/journal.json")
+            .exists()
+    );
+}
+
+#[cargo_test]
+fn no_rollback_while_resuming() {
+    init_registry();
+    // Interrupt the first run outright, then fail the hook while resuming
+    let project = sample_project(
+        r#"
+        publish = false
+        pre-release-hook = ["sh", "-c", "test -e ../resuming && exit 1; kill -9 $PPID"]
+        "#,
+    );
+
+    snapbox::cmd::Command::cargo_ui()
+        .arg("release")
+        .args(["patch", "--execute", "--no-confirm"])
+        .current_dir(project.root())
+        .assert()
+        .failure();
+
+    std::fs::write(cargo_test_support::paths::root().join("resuming"), "").unwrap();
+
+    snapbox::cmd::Command::cargo_ui()
+        .arg("release")
+        .args(["--resume", "--execute", "--no-confirm"])
+        .current_dir(project.root())
+        .assert()
+        .failure()
+        .stdout_eq(str![])
+        .stderr_eq(str![[r#"
+warning: uncommitted changes detected, please resolve before release:
+           Cargo.lock (Status(WT_MODIFIED))
+           Cargo.toml (Status(WT_MODIFIED))
+warning: push target `origin/master` doesn't exist
+    Skipping version bump of sample, already done
+    Skipping changelog for sample, already done
+    Skipping replacements for sample, already done
+error: release of sample aborted by non-zero return of pre-release hook.
+note: run `cargo release --resume` to finish the release or `cargo release undo` to roll it back
+
+"#]]);
+
+    let manifest = std::fs::read_to_string(project.root().join("Cargo.toml")).unwrap();
+    assert!(manifest.contains(r#"version = "0.1.1""#), "{manifest}");
+    assert!(
+        project
+            .root()
+            .join(".git/cargo-release/journal.json")
+            .exists()
+    );
+
+    snapbox::cmd::Command::cargo_ui()
+        .arg("release")
+        .args(["undo", "--execute", "--no-confirm"])
+        .current_dir(project.root())
+        .assert()
+        .success()
+        .stdout_eq(str![])
+        .stderr_eq(str![[r#"
+   Resetting to [..]
+
+"#]]);
+
+    let manifest = std::fs::read_to_string(project.root().join("Cargo.toml")).unwrap();
+    assert!(manifest.contains(r#"version = "0.1.0""#), "{manifest}");
+    let repo = git2::Repository::open(project.root()).unwrap();
+    let mut options = git2::StatusOptions::new();
+    options.include_ignored(false);
+    assert!(repo.statuses(Some(&mut options)).unwrap().is_empty());
+}
+
+#[cargo_test]
+fn undo_unpushed_release() {
+    init_registry();
+    let project = sample_project("publish = false\n");
+
+    snapbox::cmd::Command::cargo_ui()
+        .arg("release")
+        .args(["patch", "--execute", "--no-confirm"])
+        .current_dir(project.root())
+        .assert()
+        .failure();
+
+    snapbox::cmd::Command::cargo_ui()
+        .arg("release")
+        .args(["undo", "--execute", "--no-confirm"])
+        .current_dir(project.root())
+        .assert()
+        .success()
+        .stdout_eq(str![[r#"
+Deleted tag 'v0.1.1' (was [..])
+
+"#]])
+        .stderr_eq(str![[r#"
+    Deleting tag v0.1.1
+   Resetting to [..]
+
+"#]]);
+
+    let manifest = std::fs::read_to_string(project.root().join("Cargo.toml")).unwrap();
+    assert!(manifest.contains(r#"version = "0.1.0""#), "{manifest}");
+    let repo = git2::Repository::open(project.root()).unwrap();
+    assert!(repo.find_reference("refs/tags/v0.1.1").is_err());
+    assert!(
+        !project
+            .root()
+            .join(".git/cargo-release/journal.json")
+            .exists()
+    );
+}
+
+#[cargo_test]
+fn undo_refuses_after_push() {
+    init_registry();
+    let project = sample_project("publish = false\n");
+
+    snapbox::cmd::Command::cargo_ui()
+        .arg("release")
+        .args(["patch", "--execute", "--no-confirm"])
+        .current_dir(project.root())
+        .assert()
+        .failure();
+
+    let journal_path = project.root().join(".git/cargo-release/journal.json");
+    let journal = std::fs::read_to_string(&journal_path).unwrap();
+    let journal = journal.replace(r#""completed": ["#, r#""completed": [{"action": "push"},"#);
+    std::fs::write(&journal_path, journal).unwrap();
+
+    snapbox::cmd::Command::cargo_ui()
+        .arg("release")
+        .args(["undo", "--execute", "--no-confirm"])
+        .current_dir(project.root())
+        .assert()
+        .failure()
+        .stdout_eq(str![])
+        .stderr_eq(str![[r#"
+error: cannot undo the release, the push already happened
+
+"#]]);
+
+    assert!(journal_path.exists());
+}
+
+/// Fail only the second publish
+fn fail_second_publish() -> cargo_test_support::registry::TestRegistry {
+    let publishes = std::sync::atomic::AtomicUsize::new(0);
+    RegistryBuilder::new()
+        .alternative()
+        .http_index()
+        .http_api()
+        .add_responder("/api/v1/crates/new", move |req, server| {
+            if publishes.fetch_add(1, std::sync::atomic::Ordering::SeqCst) == 1 {
+                return server.internal_server_error(req);
+            }
+            server.check_authorized_publish(req)
+        })
+        .build()
+}
+
+#[cargo_test]
+fn resume_after_partial_publish() {
+    init_registry();
+    create_default_gitconfig();
+    let _registry = fail_second_publish();
+    let project = project()
+        .file(".gitignore", "/target\n")
+        .file(
+            "Cargo.toml",
+            r#"
+            [workspace]
+            members = ["a", "b"]
+            resolver = "3"
+            "#,
+        )
+        .file(
+            "a/Cargo.toml",
+            r#"
+            [package]
+            name = "a"
+            version = "0.1.0"
+            edition = "2024"
+            description = "A sample package"
+            license = "MIT"
+            repository = "https://example.com/sample"
+            publish = ["alternative"]
+            "#,
+        )
+        .file("a/src/lib.rs", "pub fn a() {}")
+        .file(
+            "b/Cargo.toml",
+            r#"
+            [package]
+            name = "b"
+            version = "0.2.0"
+            edition = "2024"
+            description = "A sample package"
+            license = "MIT"
+            repository = "https://example.com/sample"
+            publish = ["alternative"]
+
+            [dependencies]
+            a = { version = "0.1.0", path = "../a", registry = "alternative" }
+            "#,
+        )
+        .file("b/src/lib.rs", "pub fn b() {}")
+        .file(
+            "release.toml",
+            r#"
+            registry = "alternative"
+            push = false
+            "#,
+        )
+        .build();
+    project.process("cargo").arg("generate-lockfile").run();
+    let repo = cargo_test_support::git::init(&project.root());
+    cargo_test_support::git::add(&repo);
+    cargo_test_support::git::commit(&repo);
+
+    snapbox::cmd::Command::cargo_ui()
+        .arg("release")
+        .args([
+            "patch",
+            "--workspace",
+            "--execute",
+            "--no-confirm",
+            "--no-verify",
+        ])
+        // Publish one package at a time
+        .env("PUBLISH_GRACE_SLEEP", "1")
+        .current_dir(project.root())
+        .assert()
+        .failure()
+        .stdout_eq(str![[r#"
+[master [..]] chore: Release
+ 3 files changed, 5 insertions(+), 5 deletions(-)
+
+"#]])
+        .stderr_eq(str![[r#"
+   Upgrading a from 0.1.0 to 0.1.1
+    Updating b's dependency from 0.1.0 to 0.1.1
+   Upgrading b from 0.2.0 to 0.2.1
+  Publishing a
+    Updating `alternative` index
+   Packaging a v0.1.1 ([ROOT]/foo/a)
+    Packaged 5 files, [FILE_SIZE]B ([FILE_SIZE]B compressed)
+   Uploading a v0.1.1 ([ROOT]/foo/a)
+    Uploaded a v0.1.1 to registry `alternative`
+note: waiting for a v0.1.1 to be available at registry `alternative`
+help: you may press ctrl-c to skip waiting; the crate should be available shortly
+   Published a v0.1.1 at registry `alternative`
+  Publishing b
+    Updating `alternative` index
+   Packaging b v0.2.1 ([ROOT]/foo/b)
+    Updating `alternative` index
+    Packaged 5 files, [FILE_SIZE]B ([FILE_SIZE]B compressed)
+   Uploading b v0.2.1 ([ROOT]/foo/b)
+error: failed to publish b v0.2.1 to registry at http://127.0.0.1:[..]/
+
+Caused by:
+  failed to get a 200 OK response, got 500
+  headers:
+  	HTTP/1.1 500
+  	Content-Length: 21
+  	Connection: close
+  	
+  body:
+  internal server error
+Rolling back release
+error: cannot undo the release, a 0.1.1 was published
+note: run `cargo release --resume` to finish the release or `cargo release undo` to retry the rollback
+
+"#]]);
+
+    snapbox::cmd::Command::cargo_ui()
+        .arg("release")
+        .args(["--resume", "--execute", "--no-confirm", "--no-verify"])
+        .current_dir(project.root())
+        .assert()
+        .success()
+        .stdout_eq(str![])
+        .stderr_eq(str![[r#"
+    Skipping version bump, already done
+    Skipping changelog for a, already done
+    Skipping replacements for a, already done
+    Skipping pre-release hook for a, already done
+    Skipping changelog for b, already done
+    Skipping replacements for b, already done
+    Skipping pre-release hook for b, already done
+    Skipping release commit, already done
+    Skipping publish of a, already done
+  Publishing b
+    Updating `alternative` index
+   Packaging b v0.2.1 ([ROOT]/foo/b)
+    Updating `alternative` index
+    Packaged 5 files, [FILE_SIZE]B ([FILE_SIZE]B compressed)
+   Uploading b v0.2.1 ([ROOT]/foo/b)
+    Uploaded b v0.2.1 to registry `alternative`
+note: waiting for b v0.2.1 to be available at registry `alternative`
+help: you may press ctrl-c to skip waiting; the crate should be available shortly
+   Published b v0.2.1 at registry `alternative`
+
+"#]]);
+
+    let repo = git2::Repository::open(project.root()).unwrap();
+    assert!(repo.find_reference("refs/tags/a-v0.1.1").is_ok());
+    assert!(repo.find_reference("refs/tags/b-v0.2.1").is_ok());
+    assert!(
+        !project
+            .root()
+            .join(".git/cargo-release/journal.json")
+            .exists()
+    );
+}
@@ -0,0 +1,145 @@
+use cargo_test_support::cargo_test;
+use cargo_test_support::project;
+use cargo_test_support::registry::Package;
+use cargo_test_support::registry::RegistryBuilder;
+use snapbox::str;
+
+use crate::CargoCommand;
+use crate::create_default_gitconfig;
+use crate::init_registry;
+
+fn sample_project() -> cargo_test_support::Project {
+    init_registry();
+    create_default_gitconfig();
+    let project = project()
+        .file(".gitignore", "/target\n")
+        .file(
+            "Cargo.toml",
+            r#"
+            [package]
+            name = "sample"
+            version = "0.1.0"
+            edition = "2024"
+            "#,
+        )
+        .file("release.toml", "publish = false\n")
+        .file("src/lib.rs", "pub fn sample() {}\n")
+        .build();
+    project.process("cargo").arg("generate-lockfile").run();
+    let repo = cargo_test_support::git::init(&project.root());
+    cargo_test_support::git::add(&repo);
+    cargo_test_support::git::commit(&repo);
+    cargo_test_support::git::tag(&repo, "v0.1.0");
+    project.change_file("src/lib.rs", "pub fn sample() {}\npub fn feature() {}\n");
+    project
+        .process("git")
+        .args(&["commit", "-am", "feat: Add a feature"])
+        .run();
+    project
+}
+
+#[cargo_test]
+fn status_table() {
+    let project = sample_project();
+
+    snapbox::cmd::Command::cargo_ui()
+        .arg("release")
+        .arg("status")
+        .current_dir(project.root())
+        .assert()
+        .success()
+        .stdout_eq(str![[r#"
+PACKAGE  VERSION  TAG     COMMITS  PUBLISHED  LATEST  BUMP
+sample   0.1.0    v0.1.0  1        -          -       patch
+
+"#]])
+        .stderr_eq(str![]);
+}
+
+#[cargo_test]
+fn status_json() {
+    let project = sample_project();
+
+    snapbox::cmd::Command::cargo_ui()
+        .arg("release")
+        .args(["status", "--format", "json"])
+        .current_dir(project.root())
+        .assert()
+        .success()
+        .stdout_eq(str![[r#"
+[
+  {
+    "name": "sample",
+    "version": "0.1.0",
+    "tag": "v0.1.0",
+    "commits": 1,
+    "published": null,
+    "highest-published-version": null,
+    "suggested-bump": "patch"
+  }
+]
+
+"#]])
+        .stderr_eq(str![]);
+}
+
+#[cargo_test]
+fn status_published() {
+    init_registry();
+    let _registry = RegistryBuilder::new().alternative().http_index().build();
+    Package::new("a", "0.1.0").alternative(true).publish();
+    Package::new("b", "0.1.0").alternative(true).publish();
+    create_default_gitconfig();
+    let project = project()
+        .file(".gitignore", "/target\n")
+        .file(
+            "Cargo.toml",
+            r#"
+            [workspace]
+            members = ["a", "b"]
+            resolver = "3"
+            "#,
+        )
+        .file(
+            "a/Cargo.toml",
+            r#"
+            [package]
+            name = "a"
+            version = "0.1.0"
+            edition = "2024"
+            publish = ["alternative"]
+            "#,
+        )
+        .file("a/src/lib.rs", "pub fn a() {}\n")
+        .file(
+            "b/Cargo.toml",
+            r#"
+            [package]
+            name = "b"
+            version = "0.2.0"
+            edition = "2024"
+            publish = ["alternative"]
+            "#,
+        )
+        .file("b/src/lib.rs", "pub fn b() {}\n")
+        .file("release.toml", "registry = \"alternative\"\n")
+        .build();
+    project.process("cargo").arg("generate-lockfile").run();
+    let repo = cargo_test_support::git::init(&project.root());
+    cargo_test_support::git::add(&repo);
+    cargo_test_support::git::commit(&repo);
+
+    snapbox::cmd::Command::cargo_ui()
+        .arg("release")
+        .args(["status", "--workspace"])
+        .current_dir(project.root())
+        .assert()
+        .success()
+        .stdout_eq(str![[r#"
+PACKAGE  VERSION  TAG  COMMITS  PUBLISHED  LATEST  BUMP
+a        0.1.0    -    -        yes        0.1.0   -
+b        0.2.0    -    -        no         0.1.0   -
+
+"#]])
+        .stderr_eq(str![]);
+}
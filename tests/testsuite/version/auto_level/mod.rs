@@ -0,0 +1,35 @@
+use cargo_test_support::cargo_test;
+use cargo_test_support::compare::assert_ui;
+use cargo_test_support::current_dir;
+use cargo_test_support::file;
+
+use crate::CargoCommand;
+use crate::git_from;
+use crate::init_registry;
+
+#[cargo_test]
+fn case() {
+    init_registry();
+    let project = git_from(current_dir!().join("in"));
+    let project_root = project.root();
+    let cwd = &project_root;
+
+    let repo = git2::Repository::open(&project_root).unwrap();
+    cargo_test_support::git::tag(&repo, "v1.2.3");
+    project.change_file("src/lib.rs", "pub fn sample() {}\npub fn feature() {}\n");
+    project
+        .process("git")
+        .args(&["commit", "-am", "feat: Add a feature"])
+        .run();
+
+    snapbox::cmd::Command::cargo_ui()
+        .arg("release")
+        .args(["version", "auto", "--execute", "--no-confirm"])
+        .current_dir(cwd)
+        .assert()
+        .success()
+        .stdout_eq(file!["stdout.term.svg"])
+        .stderr_eq(file!["stderr.term.svg"]);
+
+    assert_ui().subset_matches(current_dir!().join("out"), &project_root);
+}
@@ -1,3 +1,4 @@
+mod auto_level;
 mod downgrade_error;
 mod dry_run;
 mod ignore_dependent;